@@ -0,0 +1,345 @@
+//! Live balance tracking
+//!
+//! [`BalanceBook`] merges `get_accounts_list` snapshots with the private `/account/balance`
+//! websocket channel ([`WSTopic::Balances`](crate::model::websocket::WSTopic::Balances)) into a
+//! single available/hold view per currency and account type. Websocket deltas are checked against
+//! the previous local state and periodic REST reconciliation reports any [`Discrepancy`] found.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use rust_decimal::Decimal;
+use tokio::sync::broadcast;
+
+use crate::client::Kucoin;
+use crate::error::Result;
+use crate::model::user::AccountKind;
+use crate::model::user::AccountType;
+use crate::model::user::Accounts;
+use crate::model::websocket::Balances;
+use crate::model::websocket::KucoinWebsocketMsg;
+
+const DISCREPANCY_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Balance {
+    pub total: Decimal,
+    pub available: Decimal,
+    pub hold: Decimal,
+}
+
+impl Balance {
+    fn parse(total: &str, available: &str, hold: &str) -> Result<Self> {
+        Ok(Balance { total: parse_amount(total)?, available: parse_amount(available)?, hold: parse_amount(hold)? })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BalanceKey {
+    pub currency: String,
+    pub account_type: AccountKind,
+}
+
+impl BalanceKey {
+    pub fn new(currency: &str, account_type: impl Into<AccountKind>) -> Self {
+        BalanceKey { currency: currency.to_string(), account_type: account_type.into() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscrepancySource {
+    /// A websocket delta did not lead from the previous local balance to the reported one
+    Delta,
+    /// The REST snapshot differed from the local balance
+    Reconcile,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discrepancy {
+    pub key: BalanceKey,
+    pub source: DiscrepancySource,
+    pub expected: Balance,
+    pub actual: Balance,
+}
+
+#[derive(Debug, Default)]
+struct Book {
+    balances: HashMap<BalanceKey, Balance>,
+    // accountId -> key, learned from REST snapshots
+    accounts: HashMap<String, BalanceKey>,
+}
+
+/// Consistent available/hold view per currency and account type. Cloning yields another handle to
+/// the same book.
+///
+/// Example:
+/// ``` ignore
+/// use kucoin_api::balance_book::BalanceBook;
+///
+///     let book = BalanceBook::new();
+///     book.seed(&api, None).await?;
+///     let reconciler = book.spawn_reconcile(api.clone(), None, Duration::from_secs(60));
+///
+///     while let Some(msg) = ws.try_next().await? {
+///         book.apply(&msg);
+///     }
+/// ```
+#[derive(Clone)]
+pub struct BalanceBook {
+    book: Arc<Mutex<Book>>,
+    discrepancies: broadcast::Sender<Discrepancy>,
+}
+
+impl Default for BalanceBook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BalanceBook {
+    pub fn new() -> Self {
+        let (discrepancies, _) = broadcast::channel(DISCREPANCY_CAPACITY);
+        BalanceBook { book: Arc::new(Mutex::new(Book::default())), discrepancies }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Book> {
+        self.book.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn get(&self, currency: &str, account_type: impl Into<AccountKind>) -> Option<Balance> {
+        self.lock().balances.get(&BalanceKey::new(currency, account_type)).copied()
+    }
+
+    pub fn balances(&self) -> HashMap<BalanceKey, Balance> {
+        self.lock().balances.clone()
+    }
+
+    /// Subscribes to discrepancies found while applying deltas or reconciling.
+    pub fn discrepancies(&self) -> broadcast::Receiver<Discrepancy> {
+        self.discrepancies.subscribe()
+    }
+
+    fn report(&self, found: &[Discrepancy]) {
+        for d in found {
+            tracing::warn!(?d, "Balance discrepancy");
+            let _ = self.discrepancies.send(d.clone());
+        }
+    }
+
+    /// Loads balances from `get_accounts_list`, optionally limited to a single account type,
+    /// and returns the entries that differed from the local book.
+    pub async fn seed(&self, api: &Kucoin, account_type: Option<AccountType>) -> Result<Vec<Discrepancy>> {
+        let resp = api.get_accounts_list(None, account_type).await?;
+        let accounts = match resp.data {
+            Some(d) => d,
+            None => Err(anyhow!("Failed to get accounts list: {:?}", resp.msg))?,
+        };
+        let found = self.apply_snapshot(&accounts, account_type)?;
+        self.report(&found);
+        Ok(found)
+    }
+
+    /// Alias of [`seed`](Self::seed), reads better at call sites that run it periodically.
+    pub async fn reconcile(&self, api: &Kucoin, account_type: Option<AccountType>) -> Result<Vec<Discrepancy>> {
+        self.seed(api, account_type).await
+    }
+
+    /// Replaces local balances with a REST snapshot. Balances tracked locally but absent from the
+    /// snapshot are considered zero.
+    pub fn apply_snapshot(&self, accounts: &[Accounts], account_type: Option<AccountType>) -> Result<Vec<Discrepancy>> {
+        let mut snapshot = HashMap::new();
+        let mut ids = HashMap::new();
+        for a in accounts {
+            let key = BalanceKey::new(&a.currency, a.r#type.clone());
+            snapshot.insert(key.clone(), Balance::parse(&a.balance, &a.available, &a.holds)?);
+            ids.insert(a.id.clone(), key);
+        }
+        let mut book = self.lock();
+        let mut found = Vec::new();
+        let in_scope = |k: &BalanceKey| match account_type {
+            Some(t) => k.account_type == t,
+            None => true,
+        };
+        for (key, local) in book.balances.iter().filter(|(k, _)| in_scope(k)) {
+            let remote = snapshot.get(key).copied().unwrap_or_default();
+            if *local != remote {
+                found.push(Discrepancy {
+                    key: key.clone(),
+                    source: DiscrepancySource::Reconcile,
+                    expected: *local,
+                    actual: remote,
+                });
+            }
+        }
+        book.balances.retain(|k, _| !in_scope(k));
+        book.balances.extend(snapshot);
+        book.accounts.extend(ids);
+        Ok(found)
+    }
+
+    /// Applies a websocket message. Anything other than `BalancesMsg` is ignored.
+    pub fn apply(&self, msg: &KucoinWebsocketMsg) -> Option<Discrepancy> {
+        match msg {
+            KucoinWebsocketMsg::BalancesMsg(m) => match self.apply_balance(&m.data) {
+                Ok(d) => d,
+                Err(e) => {
+                    tracing::warn!(%e, "Failed to apply balance update");
+                    None
+                },
+            },
+            _ => None,
+        }
+    }
+
+    /// Applies a single balance update, returning a discrepancy if the reported change does not
+    /// match the previous local balance. The reported absolute values always win.
+    pub fn apply_balance(&self, update: &Balances) -> Result<Option<Discrepancy>> {
+        let actual = Balance::parse(&update.total, &update.available, &update.hold)?;
+        let available_change = parse_amount(&update.available_change)?;
+        let hold_change = parse_amount(&update.hold_change)?;
+        let mut book = self.lock();
+        let key = match book.accounts.get(&update.account_id) {
+            Some(k) => k.clone(),
+            // relationEvent is prefixed by the account type, e.g. `trade.hold`. Account types this
+            // crate does not know are still tracked, under `AccountKind::Other`.
+            None => {
+                let prefix = update.relation_event.split('.').next().unwrap_or_default();
+                let account_type = match prefix.parse::<AccountType>() {
+                    Ok(t) => AccountKind::Known(t),
+                    Err(_) => {
+                        tracing::debug!(relation_event = %update.relation_event, "Unknown account type");
+                        AccountKind::Other(prefix.to_string())
+                    },
+                };
+                BalanceKey::new(&update.currency, account_type)
+            },
+        };
+        let found = book.balances.get(&key).and_then(|prev| {
+            let expected = Balance {
+                total: prev.total + available_change + hold_change,
+                available: prev.available + available_change,
+                hold: prev.hold + hold_change,
+            };
+            if expected == actual {
+                None
+            } else {
+                Some(Discrepancy { key: key.clone(), source: DiscrepancySource::Delta, expected, actual })
+            }
+        });
+        book.accounts.insert(update.account_id.clone(), key.clone());
+        book.balances.insert(key, actual);
+        drop(book);
+        if let Some(ref d) = found {
+            self.report(std::slice::from_ref(d));
+        }
+        Ok(found)
+    }
+
+    /// Spawns a task that reconciles the book with REST every `every`.
+    pub fn spawn_reconcile(
+        &self,
+        api: Kucoin,
+        account_type: Option<AccountType>,
+        every: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let book = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            loop {
+                interval.tick().await;
+                if let Err(e) = book.reconcile(&api, account_type).await {
+                    tracing::warn!(%e, "Balance reconciliation failed");
+                }
+            }
+        })
+    }
+}
+
+fn parse_amount(s: &str) -> Result<Decimal> {
+    if s.is_empty() {
+        return Ok(Decimal::ZERO);
+    }
+    Decimal::from_str(s).map_err(|_| anyhow!("Invalid amount: {}", s).into())
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal::Decimal;
+    use tokio_tungstenite::tungstenite::Message;
+
+    use crate::balance_book::BalanceBook;
+    use crate::balance_book::DiscrepancySource;
+    use crate::model::user::AccountKind;
+    use crate::model::user::AccountType;
+    use crate::model::user::Accounts;
+    use crate::model::websocket::KucoinWebsocketMsg;
+    use crate::model::APIData;
+    use crate::websocket::parse_message;
+
+    fn accounts(raw: &str) -> Vec<Accounts> {
+        serde_json::from_str::<APIData<Accounts>>(raw).unwrap().data.unwrap()
+    }
+
+    fn balance_msg(raw: &str) -> KucoinWebsocketMsg {
+        parse_message(Message::Text(raw.to_string())).unwrap()
+    }
+
+    #[test]
+    fn applies_deltas_and_reconciles() {
+        let book = BalanceBook::new();
+        let seeded = book
+            .apply_snapshot(
+                &accounts(
+                    r#"{"code":"200000","data":[{"id":"6487e5c4e2c3b40001a4f200","currency":"USDT","type":"trade","balance":"100.1","available":"100.1","holds":"0"}]}"#,
+                ),
+                None,
+            )
+            .unwrap();
+        assert!(seeded.is_empty());
+
+        // Place an order holding 10.05 USDT: exact decimal arithmetic, no spurious discrepancy
+        let hold = balance_msg(
+            r#"{"type":"message","topic":"/account/balance","subject":"account.balance","channelType":"private","data":{"accountId":"6487e5c4e2c3b40001a4f200","available":"90.05","availableChange":"-10.05","currency":"USDT","hold":"10.05","holdChange":"10.05","relationContext":{"symbol":"BTC-USDT","orderId":"6487e5c4e2c3b40001a4f28e"},"relationEvent":"trade.hold","relationEventId":"354689988084000","time":"1686627780125","total":"100.1"}}"#,
+        );
+        assert!(book.apply(&hold).is_none());
+        let b = book.get("USDT", AccountType::Trade).unwrap();
+        assert_eq!((b.available, b.hold), (Decimal::new(9005, 2), Decimal::new(1005, 2)));
+
+        // A missed update shows up as a delta discrepancy
+        let skipped = balance_msg(
+            r#"{"type":"message","topic":"/account/balance","subject":"account.balance","channelType":"private","data":{"accountId":"6487e5c4e2c3b40001a4f200","available":"70","availableChange":"-10","currency":"USDT","hold":"30.1","holdChange":"10","relationContext":{"symbol":"BTC-USDT","orderId":"6487e5c4e2c3b40001a4f28f"},"relationEvent":"trade.hold","relationEventId":"354689988084001","time":"1686627781125","total":"100.1"}}"#,
+        );
+        let d = book.apply(&skipped).unwrap();
+        assert_eq!(d.source, DiscrepancySource::Delta);
+        assert_eq!(d.expected.available, Decimal::new(8005, 2));
+
+        // REST disagrees with the local state
+        let found = book
+            .apply_snapshot(
+                &accounts(
+                    r#"{"code":"200000","data":[{"id":"6487e5c4e2c3b40001a4f200","currency":"USDT","type":"trade","balance":"100.1","available":"75","holds":"25.1"}]}"#,
+                ),
+                Some(AccountType::Trade),
+            )
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].source, DiscrepancySource::Reconcile);
+        assert_eq!(book.get("USDT", AccountType::Trade).unwrap().available, Decimal::from(75));
+    }
+
+    #[test]
+    fn tracks_unknown_account_types() {
+        let book = BalanceBook::new();
+        let update = balance_msg(
+            r#"{"type":"message","topic":"/account/balance","subject":"account.balance","channelType":"private","data":{"accountId":"6487e5c4e2c3b40001a4f201","available":"5","availableChange":"5","currency":"USDT","hold":"0","holdChange":"0","relationContext":{},"relationEvent":"option.transfer","relationEventId":"354689988084002","time":"1686627782125","total":"5"}}"#,
+        );
+        assert!(book.apply(&update).is_none());
+        assert_eq!(book.get("USDT", AccountKind::Other(String::from("option"))).unwrap().total, Decimal::from(5));
+    }
+}
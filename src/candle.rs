@@ -0,0 +1,306 @@
+//! Live candle aggregation from the `/market/match` trade stream
+//!
+//! [`CandleAggregator`] folds [`Match`] messages into OHLCV candles for any [`Klines`] interval or a
+//! custom interval in seconds. Candle boundaries are aligned the same way Kucoin aligns the candles
+//! returned by [`get_klines`](crate::client::Kucoin::get_klines), so a closed candle can be compared
+//! directly with its REST counterpart.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use futures::prelude::*;
+use rust_decimal::Decimal;
+
+use crate::error::Result;
+use crate::model::market::Candle;
+use crate::model::parameters::klines::Klines;
+use crate::model::websocket::KucoinWebsocketMsg;
+use crate::model::websocket::Match;
+
+/// Weekly candles open on Monday 00:00 UTC, the Unix epoch fell on a Thursday.
+const WEEK_OFFSET_SECS: u64 = 4 * 24 * 60 * 60;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CandleInterval {
+    Klines(Klines),
+    /// Custom interval in seconds, e.g. `Seconds(10)`. Aligned to the Unix epoch.
+    Seconds(u64),
+}
+
+impl CandleInterval {
+    pub fn as_seconds(&self) -> u64 {
+        match self {
+            CandleInterval::Klines(k) => k.as_seconds(),
+            CandleInterval::Seconds(s) => *s,
+        }
+    }
+
+    /// Interval length in seconds, at least one.
+    fn len(&self) -> u64 {
+        self.as_seconds().max(1)
+    }
+
+    /// Returns the open time in seconds of the candle containing `ts` (seconds).
+    pub fn start_of(&self, ts: u64) -> u64 {
+        let len = self.len();
+        match self {
+            CandleInterval::Klines(Klines::K1week) => {
+                let shifted = ts.saturating_sub(WEEK_OFFSET_SECS);
+                shifted - shifted % len + WEEK_OFFSET_SECS
+            },
+            _ => ts - ts % len,
+        }
+    }
+}
+
+impl From<Klines> for CandleInterval {
+    fn from(k: Klines) -> Self {
+        CandleInterval::Klines(k)
+    }
+}
+
+/// A candle emitted by the aggregator. `closed` is false while the candle is still in progress.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandleUpdate {
+    pub symbol: String,
+    pub interval: CandleInterval,
+    pub candle: Candle,
+    pub closed: bool,
+}
+
+impl CandleUpdate {
+    fn new(symbol: &str, interval: CandleInterval, state: CandleState, closed: bool) -> Self {
+        CandleUpdate { symbol: symbol.to_string(), interval, candle: state.to_candle(), closed }
+    }
+}
+
+/// Values are kept as decimals so the rendered candle matches the strings of `get_klines`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CandleState {
+    start: u64,
+    open: Decimal,
+    close: Decimal,
+    high: Decimal,
+    low: Decimal,
+    volume: Decimal,
+    amount: Decimal,
+}
+
+impl CandleState {
+    fn new(start: u64, price: Decimal, size: Decimal) -> Self {
+        CandleState { start, open: price, close: price, high: price, low: price, volume: size, amount: price * size }
+    }
+
+    fn apply(&mut self, price: Decimal, size: Decimal) {
+        self.close = price;
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.volume += size;
+        self.amount += price * size;
+    }
+
+    fn to_candle(self) -> Candle {
+        Candle {
+            time: self.start.to_string(),
+            open: self.open.normalize().to_string(),
+            close: self.close.normalize().to_string(),
+            high: self.high.normalize().to_string(),
+            low: self.low.normalize().to_string(),
+            volume: self.volume.normalize().to_string(),
+            amount: self.amount.normalize().to_string(),
+        }
+    }
+}
+
+/// Per symbol state: the in-progress candle and the open time of the last closed one, so late
+/// trades never reopen a closed candle.
+#[derive(Debug, Clone, Default)]
+struct SymbolCandles {
+    current: Option<CandleState>,
+    last_closed: Option<u64>,
+}
+
+/// Aggregates trades into candles per symbol.
+///
+/// Example:
+/// ``` rust
+/// use kucoin_api::candle::CandleAggregator;
+/// use kucoin_api::candle::CandleInterval;
+/// use kucoin_api::model::parameters::klines::Klines;
+///
+///     let mut one_min = CandleAggregator::new(Klines::K1min).unwrap();
+///     let mut ten_sec = CandleAggregator::new(CandleInterval::Seconds(10)).unwrap();
+///     assert!(CandleAggregator::new(CandleInterval::Seconds(0)).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CandleAggregator {
+    interval: CandleInterval,
+    candles: HashMap<String, SymbolCandles>,
+}
+
+impl CandleAggregator {
+    pub fn new(interval: impl Into<CandleInterval>) -> Result<Self> {
+        let interval = interval.into();
+        if interval.as_seconds() == 0 {
+            Err(anyhow!("Candle interval must be at least one second"))?
+        }
+        Ok(CandleAggregator { interval, candles: HashMap::new() })
+    }
+
+    pub fn interval(&self) -> CandleInterval {
+        self.interval
+    }
+
+    /// Applies a trade and returns the resulting updates: the previous candle if the trade
+    /// crossed a boundary (`closed == true`), followed by the in-progress candle.
+    pub fn update(&mut self, trade: &Match) -> Result<Vec<CandleUpdate>> {
+        let price = Decimal::from_str(&trade.price).map_err(|_| anyhow!("Invalid match price: {}", trade.price))?;
+        let size = Decimal::from_str(&trade.size).map_err(|_| anyhow!("Invalid match size: {}", trade.size))?;
+        // Match time is reported in nanoseconds
+        let nanos: u64 = trade.time.parse().map_err(|_| anyhow!("Invalid match time: {}", trade.time))?;
+        Ok(self.apply(&trade.symbol, nanos / 1_000_000_000, price, size))
+    }
+
+    fn apply(&mut self, symbol: &str, ts: u64, price: Decimal, size: Decimal) -> Vec<CandleUpdate> {
+        let interval = self.interval;
+        let start = interval.start_of(ts);
+        let mut updates = Vec::new();
+        let candles = self.candles.entry(symbol.to_string()).or_default();
+        // Late trades for an already closed candle are dropped
+        if candles.last_closed.is_some_and(|closed| start <= closed) {
+            return updates;
+        }
+        match &mut candles.current {
+            Some(state) if state.start == start => state.apply(price, size),
+            Some(state) if state.start > start => return updates,
+            Some(state) => {
+                updates.push(CandleUpdate::new(symbol, interval, *state, true));
+                candles.last_closed = Some(state.start);
+                *state = CandleState::new(start, price, size);
+            },
+            None => candles.current = Some(CandleState::new(start, price, size)),
+        }
+        if let Some(state) = candles.current {
+            updates.push(CandleUpdate::new(symbol, interval, state, false));
+        }
+        updates
+    }
+
+    /// Closes and returns every candle whose interval ended at or before `now` (seconds). Useful to
+    /// close candles on a timer when no new trade arrives after the boundary.
+    pub fn close_expired(&mut self, now: u64) -> Vec<CandleUpdate> {
+        let len = self.interval.len();
+        let mut updates = Vec::new();
+        for (symbol, candles) in self.candles.iter_mut() {
+            if let Some(state) = candles.current.filter(|s| s.start + len <= now) {
+                updates.push(CandleUpdate::new(symbol, self.interval, state, true));
+                candles.last_closed = Some(state.start);
+                candles.current = None;
+            }
+        }
+        updates
+    }
+
+    /// Returns the in-progress candle for a symbol, if any.
+    pub fn current(&self, symbol: &str) -> Option<Candle> {
+        self.candles.get(symbol).and_then(|c| c.current).map(|s| s.to_candle())
+    }
+
+    /// Turns a websocket message stream into a stream of candle updates. Messages other than
+    /// `MatchMsg` are skipped.
+    pub fn aggregate<S>(mut self, stream: S) -> impl Stream<Item = Result<CandleUpdate>>
+    where
+        S: Stream<Item = Result<KucoinWebsocketMsg>>,
+    {
+        stream
+            .map(move |msg| match msg {
+                Ok(KucoinWebsocketMsg::MatchMsg(m)) => self.update(&m.data),
+                Ok(_) => Ok(vec![]),
+                Err(e) => Err(e),
+            })
+            .map_ok(|updates| stream::iter(updates.into_iter().map(Ok)))
+            .try_flatten()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal::Decimal;
+    use tokio_tungstenite::tungstenite::Message;
+
+    use crate::candle::CandleAggregator;
+    use crate::candle::CandleInterval;
+    use crate::model::parameters::klines::Klines;
+    use crate::model::websocket::KucoinWebsocketMsg;
+    use crate::websocket::parse_message;
+
+    #[test]
+    fn interval_alignment() {
+        // 2023-06-21T10:17:33Z, a Wednesday
+        let ts = 1_687_342_653;
+        assert_eq!(CandleInterval::Klines(Klines::K1min).start_of(ts), 1_687_342_620);
+        assert_eq!(CandleInterval::Klines(Klines::K1hour).start_of(ts), 1_687_341_600);
+        assert_eq!(CandleInterval::Seconds(10).start_of(ts), 1_687_342_650);
+        // Monday 2023-06-19T00:00:00Z
+        assert_eq!(CandleInterval::Klines(Klines::K1week).start_of(ts), 1_687_132_800);
+    }
+
+    #[test]
+    fn aggregates_and_closes_candles() {
+        let mut agg = CandleAggregator::new(Klines::K1min).unwrap();
+        let first = agg.apply("BTC-USDT", 60, Decimal::from(10), Decimal::ONE);
+        assert_eq!(first.len(), 1);
+        assert!(!first[0].closed);
+
+        agg.apply("BTC-USDT", 90, Decimal::from(12), Decimal::TWO);
+        agg.apply("BTC-USDT", 100, Decimal::from(9), Decimal::ONE);
+        let updates = agg.apply("BTC-USDT", 125, Decimal::from(11), Decimal::ONE);
+        assert_eq!(updates.len(), 2);
+
+        let closed = &updates[0];
+        assert!(closed.closed);
+        assert_eq!(closed.candle.time, "60");
+        assert_eq!(closed.candle.open, "10");
+        assert_eq!(closed.candle.high, "12");
+        assert_eq!(closed.candle.low, "9");
+        assert_eq!(closed.candle.close, "9");
+        assert_eq!(closed.candle.volume, "4");
+        assert_eq!(closed.candle.amount, "43");
+
+        assert!(!updates[1].closed);
+        assert_eq!(updates[1].candle.time, "120");
+
+        // Late trade for the closed candle is ignored
+        assert!(agg.apply("BTC-USDT", 70, Decimal::ONE, Decimal::ONE).is_empty());
+
+        let expired = agg.close_expired(180);
+        assert_eq!(expired.len(), 1);
+        assert!(expired[0].closed);
+        assert!(agg.current("BTC-USDT").is_none());
+        // Nor does a late trade reopen a candle closed by the timer
+        assert!(agg.apply("BTC-USDT", 150, Decimal::ONE, Decimal::ONE).is_empty());
+        assert!(agg.close_expired(240).is_empty());
+    }
+
+    #[test]
+    fn keeps_decimal_strings() {
+        let mut agg = CandleAggregator::new(CandleInterval::Seconds(10)).unwrap();
+        let trades = [
+            r#"{"type":"message","topic":"/market/match:BTC-USDT","subject":"trade.l3match","data":{"makerOrderId":"6487e5c4e2c3b40001a4f28e","price":"0.1","sequence":"10976028","side":"buy","size":"0.1","symbol":"BTC-USDT","takerOrderId":"6487e5c5e2c3b40001a4f29a","time":"1686627781000000000","tradeId":"10976028","type":"match"}}"#,
+            r#"{"type":"message","topic":"/market/match:BTC-USDT","subject":"trade.l3match","data":{"makerOrderId":"6487e5c4e2c3b40001a4f28f","price":"0.2","sequence":"10976029","side":"buy","size":"0.20","symbol":"BTC-USDT","takerOrderId":"6487e5c5e2c3b40001a4f29b","time":"1686627782000000000","tradeId":"10976029","type":"match"}}"#,
+        ];
+        let mut updates = Vec::new();
+        for trade in trades {
+            match parse_message(Message::Text(trade.to_string())).unwrap() {
+                KucoinWebsocketMsg::MatchMsg(m) => updates = agg.update(&m.data).unwrap(),
+                msg => panic!("Unexpected message {:?}", msg),
+            }
+        }
+        let candle = &updates[0].candle;
+        assert_eq!(candle.time, "1686627780");
+        assert_eq!(candle.high, "0.2");
+        assert_eq!(candle.volume, "0.3");
+        assert_eq!(candle.amount, "0.05");
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use anyhow::anyhow;
 use base64::encode;
 use hmac::Hmac;
 use hmac::Mac;
@@ -7,6 +8,7 @@ use reqwest;
 use reqwest::header::HeaderMap;
 use reqwest::header::HeaderName;
 use reqwest::header::HeaderValue;
+use reqwest::header::CONTENT_TYPE;
 use reqwest::StatusCode;
 use serde_json::json;
 use sha2::Sha256;
@@ -50,6 +52,7 @@ pub struct Kucoin {
     credentials: Option<Credentials>,
     pub prefix: String,
     pub client: reqwest::Client,
+    dry_run: bool,
 }
 
 impl Kucoin {
@@ -62,7 +65,46 @@ impl Kucoin {
             KucoinEnv::Live => String::from("https://api.kucoin.com"),
             KucoinEnv::Sandbox => String::from("https://openapi-sandbox.kucoin.com"),
         };
-        Ok(Kucoin { credentials, prefix, client })
+        Ok(Kucoin { credentials, prefix, client, dry_run: false })
+    }
+
+    /// Enables or disables dry run mode. While enabled, order placement is sent to the exchange's
+    /// order test endpoints, which validate the order without submitting it, and the order is
+    /// logged. Order types without a test endpoint (batch, stop, OCO and synchronous HF orders)
+    /// are rejected with an error instead of being placed.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Returns a copy of the client with dry run mode enabled, e.g. to test a single order with
+    /// `api.dry_run().post_limit_order(..)`.
+    pub fn dry_run(&self) -> Kucoin {
+        let mut api = self.clone();
+        api.dry_run = true;
+        api
+    }
+
+    /// Endpoint an order is placed on: its `/test` variant when in dry run mode.
+    pub(crate) fn order_endpoint(&self, endpoint: &str, order: &impl std::fmt::Debug) -> String {
+        if self.dry_run {
+            tracing::info!(endpoint, ?order, "Dry run, sending order to test endpoint");
+            format!("{}/test", endpoint)
+        } else {
+            endpoint.to_string()
+        }
+    }
+
+    /// Fails when in dry run mode, for order endpoints without a test variant.
+    pub(crate) fn ensure_live(&self, endpoint: &str, order: &impl std::fmt::Debug) -> Result<()> {
+        if self.dry_run {
+            tracing::info!(endpoint, ?order, "Dry run, order not sent");
+            Err(anyhow!("{} has no test endpoint and is not available in dry run mode", endpoint))?
+        }
+        Ok(())
     }
 
     // Generic get request for internal library use.
@@ -120,6 +162,18 @@ impl Kucoin {
         }
     }
 
+    /// POST with a pre-serialized JSON body, signed with [`sign_headers_json`](Self::sign_headers_json).
+    pub async fn post_json(&self, url: String, sign: Option<HeaderMap>, body: String) -> Result<reqwest::Response> {
+        let req_url = reqwest::Url::parse(&url)?;
+        if let Some(s) = sign {
+            let resp =
+                self.client.post(req_url).headers(s).header(CONTENT_TYPE, "application/json").body(body).send().await?;
+            Ok(resp)
+        } else {
+            panic!("Unsigned POST request...")
+        }
+    }
+
     pub async fn delete(&self, url: String, sign: Option<HeaderMap>) -> Result<reqwest::Response> {
         let req_url = reqwest::Url::parse(&url)?;
         if let Some(s) = sign {
@@ -143,20 +197,8 @@ impl Kucoin {
         query: Option<String>,
         method: Method,
     ) -> Result<HeaderMap> {
-        let mut headers = HeaderMap::new();
         let nonce = get_time().to_string();
-        let mut api_key: &str = "";
-        let mut secret_key: &str = "";
-        let mut passphrase: &str = "";
         let mut str_to_sign: String = String::new();
-        match &self.credentials {
-            Some(c) => {
-                api_key = &c.api_key;
-                secret_key = &c.secret_key;
-                passphrase = &c.passphrase;
-            },
-            None => (),
-        }
         match method {
             Method::GET => {
                 let meth = "GET";
@@ -187,6 +229,27 @@ impl Kucoin {
                 }
             },
         }
+        self.signed(nonce, str_to_sign)
+    }
+
+    /// Signs a request whose body is an already serialized JSON document. Used by endpoints that
+    /// take nested parameters which cannot be expressed as a flat string map.
+    pub fn sign_headers_json(&self, endpoint: String, body: &str, method: Method) -> Result<HeaderMap> {
+        let nonce = get_time().to_string();
+        let str_to_sign = format!("{}{}{}{}", nonce, method.as_str(), endpoint, body);
+        self.signed(nonce, str_to_sign)
+    }
+
+    fn signed(&self, nonce: String, str_to_sign: String) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        let mut api_key: &str = "";
+        let mut secret_key: &str = "";
+        let mut passphrase: &str = "";
+        if let Some(c) = &self.credentials {
+            api_key = &c.api_key;
+            secret_key = &c.secret_key;
+            passphrase = &c.passphrase;
+        }
         let mut hmac_sign = HmacSha256::new_varkey(secret_key.as_bytes()).expect("HMAC can take key of any size");
         hmac_sign.input(str_to_sign.as_bytes());
         let sign_result = hmac_sign.result();
@@ -0,0 +1,204 @@
+//! Currency and chain metadata resolved at runtime
+//!
+//! [`CurrencyRegistry`] caches the currency list of
+//! [`get_currency_details`](crate::client::Kucoin::get_currency_details) with the per chain
+//! deposit and withdrawal settings (minimums, fees, confirmations, contract addresses), and
+//! resolves currency codes and chain ids or names against it. It replaces the fixed `Chain` and
+//! `Fiat` enums, which cannot keep up with newly listed currencies and chains.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::anyhow;
+
+use crate::client::Kucoin;
+use crate::error::Result;
+use crate::model::market::ChainId;
+use crate::model::market::CurrencyChain;
+use crate::model::market::CurrencyDetail;
+
+#[derive(Debug, Default)]
+struct Registry {
+    /// Keyed by upper case currency code
+    currencies: HashMap<String, CurrencyDetail>,
+    refreshed_at: Option<Instant>,
+}
+
+/// Runtime currency and chain metadata. Cloning yields another handle to the same registry.
+///
+/// Example:
+/// ``` ignore
+/// use kucoin_api::currency_registry::CurrencyRegistry;
+///
+///     let registry = CurrencyRegistry::new();
+///     registry.refresh(&api).await?;
+///     let refresher = registry.spawn_refresh(api.clone(), Duration::from_secs(3600))?;
+///
+///     let chain = registry.resolve_chain("USDT", Some("TRC20"))?;
+///     let addresses = api.get_deposit_addresses("USDT", Some(&chain), None).await?;
+/// ```
+#[derive(Clone, Default)]
+pub struct CurrencyRegistry {
+    inner: Arc<Mutex<Registry>>,
+}
+
+impl CurrencyRegistry {
+    pub fn new() -> Self {
+        CurrencyRegistry::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Registry> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Replaces the registry content with the full currency list. Returns the number of currencies.
+    pub async fn refresh(&self, api: &Kucoin) -> Result<usize> {
+        let resp = api.get_currency_details().await?;
+        match resp.data {
+            Some(currencies) => Ok(self.load(currencies)),
+            None => Err(anyhow!("Failed to get currencies: {}", resp.msg.unwrap_or_default()))?,
+        }
+    }
+
+    /// Refreshes a single currency, e.g. one listed since the last full refresh.
+    pub async fn refresh_currency(&self, api: &Kucoin, currency: &str) -> Result<CurrencyDetail> {
+        let resp = api.get_currency_detail(currency, None).await?;
+        match resp.data {
+            Some(detail) => {
+                self.lock().currencies.insert(detail.currency.to_ascii_uppercase(), detail.clone());
+                Ok(detail)
+            },
+            None => Err(anyhow!("Unknown currency {}: {}", currency, resp.msg.unwrap_or_default()))?,
+        }
+    }
+
+    /// Replaces the registry content. Returns the number of currencies.
+    pub fn load(&self, currencies: Vec<CurrencyDetail>) -> usize {
+        let mut registry = self.lock();
+        registry.currencies = currencies.into_iter().map(|c| (c.currency.to_ascii_uppercase(), c)).collect();
+        registry.refreshed_at = Some(Instant::now());
+        registry.currencies.len()
+    }
+
+    pub fn refreshed_at(&self) -> Option<Instant> {
+        self.lock().refreshed_at
+    }
+
+    /// Whether the registry was never refreshed or not within `max_age`.
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.lock().refreshed_at.is_none_or(|t| t.elapsed() > max_age)
+    }
+
+    /// Currency codes, sorted.
+    pub fn currencies(&self) -> Vec<String> {
+        let mut codes: Vec<String> = self.lock().currencies.values().map(|c| c.currency.clone()).collect();
+        codes.sort();
+        codes
+    }
+
+    /// Looks a currency up by its code, ignoring case, or by its unique name.
+    pub fn currency(&self, currency: &str) -> Option<CurrencyDetail> {
+        let registry = self.lock();
+        registry
+            .currencies
+            .get(&currency.to_ascii_uppercase())
+            .or_else(|| registry.currencies.values().find(|c| c.name.eq_ignore_ascii_case(currency)))
+            .cloned()
+    }
+
+    /// Looks a chain of `currency` up by its id or name, ignoring case.
+    pub fn chain(&self, currency: &str, chain: &str) -> Option<CurrencyChain> {
+        self.currency(currency)?.chain(chain).cloned()
+    }
+
+    /// Resolves the chain to use for `currency`: the given chain id or name, or the currency's only
+    /// chain when none is given.
+    pub fn resolve_chain(&self, currency: &str, chain: Option<&str>) -> Result<ChainId> {
+        match self.currency(currency) {
+            Some(detail) => detail.resolve_chain(chain),
+            None => Err(anyhow!("Unknown currency {}", currency))?,
+        }
+    }
+
+    /// Spawns a task refreshing the registry every `every`. Failed refreshes keep the previous
+    /// content.
+    pub fn spawn_refresh(&self, api: Kucoin, every: Duration) -> Result<tokio::task::JoinHandle<()>> {
+        if every.is_zero() {
+            Err(anyhow!("Refresh interval must not be zero"))?
+        }
+        let registry = self.clone();
+        Ok(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            loop {
+                interval.tick().await;
+                match registry.refresh(&api).await {
+                    Ok(n) => tracing::debug!(currencies = n, "Currency registry refreshed"),
+                    Err(e) => tracing::warn!(%e, "Currency registry refresh failed"),
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::client::Kucoin;
+    use crate::client::KucoinEnv;
+    use crate::currency_registry::CurrencyRegistry;
+    use crate::model::market::CurrencyDetail;
+
+    #[test]
+    fn resolves_currencies_and_chains() {
+        let currencies: Vec<CurrencyDetail> = serde_json::from_str(
+            r#"[{"currency":"USDT","name":"USDT","fullName":"Tether","precision":8,"confirms":null,
+            "contractAddress":null,"isMarginEnabled":true,"isDebitEnabled":true,"chains":[
+            {"chainName":"TRC20","withdrawalMinSize":"10","depositMinSize":"1","withdrawFeeRate":"0",
+            "withdrawalMinFee":"1","isWithdrawEnabled":true,"isDepositEnabled":true,"confirms":1,"preConfirms":1,
+            "contractAddress":"TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t","withdrawPrecision":8,
+            "maxWithdraw":null,"maxDeposit":null,"needTag":false,"chainId":"trx"},
+            {"chainName":"ERC20","withdrawalMinSize":"10","depositMinSize":"1","withdrawFeeRate":"0",
+            "withdrawalMinFee":"5","isWithdrawEnabled":true,"isDepositEnabled":true,"confirms":64,"preConfirms":64,
+            "contractAddress":"0xdac17f958d2ee523a2206206994597c13d831ec7","withdrawPrecision":8,
+            "maxWithdraw":null,"maxDeposit":null,"needTag":false,"chainId":"eth"}]},
+            {"currency":"XRP","name":"XRP","fullName":"XRP","precision":6,"confirms":null,
+            "contractAddress":null,"isMarginEnabled":true,"isDebitEnabled":true,"chains":[
+            {"chainName":"XRP","withdrawalMinSize":"20","depositMinSize":"1","withdrawFeeRate":"0",
+            "withdrawalMinFee":"0.5","isWithdrawEnabled":true,"isDepositEnabled":true,"confirms":1,"preConfirms":1,
+            "contractAddress":null,"withdrawPrecision":6,
+            "maxWithdraw":null,"maxDeposit":null,"needTag":true,"chainId":"xrp"}]},
+            {"currency":"OLD","name":"OLD","fullName":"Delisted","precision":8,"confirms":null,
+            "contractAddress":null,"isMarginEnabled":false,"isDebitEnabled":false,"chains":null}]"#,
+        )
+        .unwrap();
+        let registry = CurrencyRegistry::new();
+        assert!(registry.is_stale(Duration::from_secs(60)));
+        assert_eq!(registry.load(currencies), 3);
+        assert!(!registry.is_stale(Duration::from_secs(60)));
+        assert_eq!(registry.currencies(), ["OLD", "USDT", "XRP"]);
+
+        assert_eq!(registry.currency("usdt").unwrap().full_name, "Tether");
+        assert!(registry.currency("OLD").unwrap().chains.is_empty());
+        assert_eq!(
+            registry.chain("USDT", "trc20").unwrap().contract_address.as_deref(),
+            Some("TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t")
+        );
+        assert_eq!(registry.resolve_chain("USDT", Some("ERC20")).unwrap().as_str(), "eth");
+        assert_eq!(registry.resolve_chain("xrp", None).unwrap().as_str(), "xrp");
+        assert!(registry.resolve_chain("USDT", None).is_err());
+        assert!(registry.resolve_chain("USDT", Some("sol")).is_err());
+        assert!(registry.resolve_chain("DOGE", None).is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_a_zero_refresh_interval() {
+        let api = Kucoin::new(KucoinEnv::Sandbox, None).unwrap();
+        assert!(CurrencyRegistry::new().spawn_refresh(api, Duration::ZERO).is_err());
+    }
+}
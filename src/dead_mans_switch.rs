@@ -0,0 +1,277 @@
+//! Dead man's switch for resting orders
+//!
+//! [`DeadMansSwitch`] keeps KuCoin's HF `cancel-all-after` countdown armed
+//! ([`set_hf_cancel_all_after`](crate::client::Kucoin::set_hf_cancel_all_after)) by re-arming it
+//! periodically, so the exchange cancels the HF orders of the watched symbols if the process dies.
+//! As the countdown only covers HF orders, the switch also cancels orders itself through REST once
+//! websocket or REST connectivity has been lost for longer than a threshold. A clean shutdown
+//! should call [`DeadMansSwitch::disarm`].
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::anyhow;
+use tokio::sync::watch;
+use tokio::sync::Mutex as AsyncMutex;
+
+use crate::client::Kucoin;
+use crate::error::Result;
+
+/// Bounds of the `cancel-all-after` timeout accepted by the exchange, in seconds.
+pub const CANCEL_ALL_AFTER_MIN: u64 = 5;
+pub const CANCEL_ALL_AFTER_MAX: u64 = 86400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectivityLoss {
+    Websocket,
+    Rest,
+}
+
+#[derive(Debug)]
+struct Connectivity {
+    last_ws: Option<Instant>,
+    last_rest: Instant,
+}
+
+impl Connectivity {
+    /// Websocket loss is only detected once a heartbeat has been received.
+    fn lost(&self, threshold: Duration, now: Instant) -> Option<ConnectivityLoss> {
+        if self.last_ws.is_some_and(|t| now.saturating_duration_since(t) > threshold) {
+            Some(ConnectivityLoss::Websocket)
+        } else if now.saturating_duration_since(self.last_rest) > threshold {
+            Some(ConnectivityLoss::Rest)
+        } else {
+            None
+        }
+    }
+}
+
+/// Periodically re-arms the HF `cancel-all-after` countdown for a set of symbols (all symbols
+/// when empty) and falls back to canceling orders locally when connectivity is lost. Cloning
+/// yields another handle to the same switch.
+///
+/// Example:
+/// ``` ignore
+/// use kucoin_api::dead_mans_switch::DeadMansSwitch;
+///
+///     let mut switch = DeadMansSwitch::new(api.clone(), Duration::from_secs(30), &["BTC-USDT"])?;
+///     switch.fallback_after(Duration::from_secs(20))?;
+///     let task = switch.spawn();
+///
+///     while let Some(msg) = ws.try_next().await? {
+///         switch.heartbeat();
+///     }
+///     switch.disarm().await?;
+/// ```
+#[derive(Clone)]
+pub struct DeadMansSwitch {
+    api: Kucoin,
+    symbols: Vec<String>,
+    timeout: Duration,
+    interval: Duration,
+    fallback_after: Duration,
+    connectivity: Arc<Mutex<Connectivity>>,
+    shutdown: Arc<watch::Sender<bool>>,
+    /// Held across every arm and disarm request, so a disarm is never overtaken by an arm request
+    /// still in flight. True once disarmed.
+    disarmed: Arc<AsyncMutex<bool>>,
+}
+
+impl DeadMansSwitch {
+    /// `timeout` is the exchange side countdown, between CANCEL_ALL_AFTER_MIN and
+    /// CANCEL_ALL_AFTER_MAX seconds. By default it is re-armed every third of `timeout` and the
+    /// local fallback triggers after `timeout` without connectivity.
+    pub fn new(api: Kucoin, timeout: Duration, symbols: &[&str]) -> Result<Self> {
+        if !(CANCEL_ALL_AFTER_MIN..=CANCEL_ALL_AFTER_MAX).contains(&timeout.as_secs()) {
+            Err(anyhow!(
+                "cancel all after timeout must be between {} and {} seconds",
+                CANCEL_ALL_AFTER_MIN,
+                CANCEL_ALL_AFTER_MAX
+            ))?
+        }
+        let (shutdown, _) = watch::channel(false);
+        Ok(DeadMansSwitch {
+            api,
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            timeout,
+            interval: timeout / 3,
+            fallback_after: timeout,
+            connectivity: Arc::new(Mutex::new(Connectivity { last_ws: None, last_rest: Instant::now() })),
+            shutdown: Arc::new(shutdown),
+            disarmed: Arc::new(AsyncMutex::new(false)),
+        })
+    }
+
+    /// Re-arm period, at most half of `timeout` so a late re-arm does not let the countdown run
+    /// out.
+    pub fn interval(&mut self, interval: Duration) -> Result<&mut Self> {
+        if interval.is_zero() || interval > self.timeout / 2 {
+            Err(anyhow!("Re-arm interval must be positive and at most half of the timeout: {:?}", interval))?
+        }
+        self.interval = interval;
+        Ok(self)
+    }
+
+    /// Connectivity loss after which the local fallback cancels orders, at most `timeout` since
+    /// the exchange cancels them by then.
+    pub fn fallback_after(&mut self, fallback_after: Duration) -> Result<&mut Self> {
+        if fallback_after.is_zero() || fallback_after > self.timeout {
+            Err(anyhow!("Fallback threshold must be positive and at most the timeout: {:?}", fallback_after))?
+        }
+        self.fallback_after = fallback_after;
+        Ok(self)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connectivity> {
+        self.connectivity.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Records websocket activity. Call it for every message received.
+    pub fn heartbeat(&self) {
+        self.lock().last_ws = Some(Instant::now());
+    }
+
+    /// Returns which connection has been silent for longer than the fallback threshold, if any.
+    pub fn connectivity_lost(&self) -> Option<ConnectivityLoss> {
+        self.lock().lost(self.fallback_after, Instant::now())
+    }
+
+    /// Arms the countdown once. A successful call counts as REST activity. Fails once the switch
+    /// has been disarmed.
+    pub async fn arm(&self) -> Result<()> {
+        let disarmed = self.disarmed.lock().await;
+        if *disarmed {
+            Err(anyhow!("Dead man's switch is disarmed"))?
+        }
+        let symbols: Vec<&str> = self.symbols.iter().map(|s| s.as_str()).collect();
+        let symbols = if symbols.is_empty() { None } else { Some(symbols.as_slice()) };
+        let resp = self.api.set_hf_cancel_all_after(self.timeout.as_secs() as i64, symbols).await?;
+        if resp.code != "200000" {
+            Err(anyhow!("Failed to arm cancel all after: {}: {}", resp.code, resp.msg.unwrap_or_default()))?
+        }
+        self.lock().last_rest = Instant::now();
+        Ok(())
+    }
+
+    /// Spawns the task re-arming the countdown every `interval` until `disarm` is called.
+    pub fn spawn(&self) -> tokio::task::JoinHandle<()> {
+        let switch = self.clone();
+        let mut shutdown = self.shutdown.subscribe();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(switch.interval);
+            let mut tripped = false;
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {},
+                    _ = shutdown.changed() => break,
+                }
+                if *shutdown.borrow() {
+                    break;
+                }
+                if let Err(e) = switch.arm().await {
+                    tracing::warn!(%e, "Failed to re-arm cancel all after");
+                }
+                if *shutdown.borrow() {
+                    break;
+                }
+                match switch.connectivity_lost() {
+                    Some(loss) if !tripped => {
+                        tracing::warn!(?loss, "Connectivity lost, canceling orders");
+                        tripped = switch.cancel_orders().await.is_ok();
+                    },
+                    Some(_) => {},
+                    None => tripped = false,
+                }
+            }
+        })
+    }
+
+    /// Cancels the open orders of the watched symbols, both regular and HF.
+    pub async fn cancel_orders(&self) -> Result<()> {
+        let mut result = Ok(());
+        if self.symbols.is_empty() {
+            if let Err(e) = self.api.cancel_all_orders(None, None).await {
+                result = Err(e);
+            }
+            if let Err(e) = self.api.cancel_all_hf_orders().await {
+                result = Err(e);
+            }
+        }
+        for symbol in &self.symbols {
+            if let Err(e) = self.api.cancel_all_orders(Some(symbol.as_str()), None).await {
+                result = Err(e);
+            }
+            if let Err(e) = self.api.cancel_hf_orders(symbol).await {
+                result = Err(e);
+            }
+        }
+        if let Err(e) = &result {
+            tracing::error!(%e, "Failed to cancel orders");
+        }
+        result
+    }
+
+    /// Stops the re-arming task and disarms the countdown, leaving resting orders in place. Waits
+    /// for an arm request in flight to complete first, and no arm request is sent afterwards.
+    pub async fn disarm(&self) -> Result<()> {
+        let _ = self.shutdown.send(true);
+        let mut disarmed = self.disarmed.lock().await;
+        *disarmed = true;
+        let resp = self.api.set_hf_cancel_all_after(-1, None).await?;
+        if resp.code != "200000" {
+            Err(anyhow!("Failed to disarm cancel all after: {}: {}", resp.code, resp.msg.unwrap_or_default()))?
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use crate::client::Kucoin;
+    use crate::client::KucoinEnv;
+    use crate::dead_mans_switch::Connectivity;
+    use crate::dead_mans_switch::ConnectivityLoss;
+    use crate::dead_mans_switch::DeadMansSwitch;
+
+    #[test]
+    fn detects_connectivity_loss() {
+        let api = Kucoin::new(KucoinEnv::Sandbox, None).unwrap();
+        assert!(DeadMansSwitch::new(api.clone(), Duration::from_secs(1), &[]).is_err());
+        let switch = DeadMansSwitch::new(api, Duration::from_secs(30), &["BTC-USDT"]).unwrap();
+        assert_eq!(switch.interval, Duration::from_secs(10));
+        assert_eq!(switch.connectivity_lost(), None);
+
+        let threshold = Duration::from_secs(10);
+        let start = Instant::now();
+        let mut state = Connectivity { last_ws: None, last_rest: start };
+        assert_eq!(state.lost(threshold, start + Duration::from_secs(5)), None);
+        assert_eq!(state.lost(threshold, start + Duration::from_secs(11)), Some(ConnectivityLoss::Rest));
+        state.last_rest = start + Duration::from_secs(20);
+        state.last_ws = Some(start);
+        assert_eq!(state.lost(threshold, start + Duration::from_secs(21)), Some(ConnectivityLoss::Websocket));
+    }
+
+    #[test]
+    fn validates_interval_and_fallback() {
+        let api = Kucoin::new(KucoinEnv::Sandbox, None).unwrap();
+        let mut switch = DeadMansSwitch::new(api, Duration::from_secs(30), &["BTC-USDT"]).unwrap();
+        assert!(switch.interval(Duration::ZERO).is_err());
+        assert!(switch.interval(Duration::from_secs(30)).is_err());
+        assert!(switch.interval(Duration::from_secs(16)).is_err());
+        assert!(switch.interval(Duration::from_secs(15)).is_ok());
+        assert_eq!(switch.interval, Duration::from_secs(15));
+
+        assert!(switch.fallback_after(Duration::ZERO).is_err());
+        assert!(switch.fallback_after(Duration::from_secs(31)).is_err());
+        assert!(switch.fallback_after(Duration::from_secs(30)).is_ok());
+        assert!(switch.fallback_after(Duration::from_secs(20)).is_ok());
+        assert_eq!(switch.fallback_after, Duration::from_secs(20));
+    }
+}
@@ -6,6 +6,8 @@ pub enum Error {
     Websocket(#[from] tokio_tungstenite::tungstenite::Error),
     #[error("REST Call error")]
     HTTP(#[from] reqwest::Error),
+    #[error("IO error")]
+    IO(#[from] std::io::Error),
     #[error("Failed to join async task error")]
     Join(#[from] tokio::task::JoinError),
     #[error("Failed to parse url")]
@@ -0,0 +1,222 @@
+//! Trading fee lookup and fee-aware order calculations
+//!
+//! [`FeeCache`] keeps the fee rates returned by
+//! [`get_trade_fees`](crate::client::Kucoin::get_trade_fees) per symbol for a configurable time
+//! and fetches missing or stale symbols in batches. [`FeeRate::estimate`] computes the expected fee
+//! and net amount of an order, with the fee charged in the quote currency.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::anyhow;
+use rust_decimal::Decimal;
+
+use crate::client::Kucoin;
+use crate::error::Result;
+use crate::model::user::TradeFee;
+use crate::user::TRADE_FEES_MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Liquidity {
+    Maker,
+    Taker,
+}
+
+impl FromStr for Liquidity {
+    type Err = crate::error::Error;
+
+    /// Parses the `liquidity` field of fills.
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "maker" => Ok(Liquidity::Maker),
+            "taker" => Ok(Liquidity::Taker),
+            _ => Err(anyhow!("Invalid liquidity: {}", s))?,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FeeRate {
+    pub maker: Decimal,
+    pub taker: Decimal,
+}
+
+/// Expected outcome of an order, in the quote currency. `net` is what a sell receives or what a
+/// buy spends, fee included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub notional: Decimal,
+    pub fee: Decimal,
+    pub net: Decimal,
+}
+
+impl FeeRate {
+    pub fn rate(&self, liquidity: Liquidity) -> Decimal {
+        match liquidity {
+            Liquidity::Maker => self.maker,
+            Liquidity::Taker => self.taker,
+        }
+    }
+
+    /// Expected fee in the quote currency.
+    pub fn fee(&self, liquidity: Liquidity, price: Decimal, size: Decimal) -> Decimal {
+        price * size * self.rate(liquidity)
+    }
+
+    /// Expected fee and net amount for an order of `size` at `price`. `side` is buy or sell.
+    pub fn estimate(&self, side: &str, liquidity: Liquidity, price: Decimal, size: Decimal) -> Result<FeeEstimate> {
+        let notional = price * size;
+        let fee = self.fee(liquidity, price, size);
+        let net = match side {
+            "buy" => notional + fee,
+            "sell" => notional - fee,
+            _ => Err(anyhow!("Invalid side: {}", side))?,
+        };
+        Ok(FeeEstimate { notional, fee, net })
+    }
+
+    /// Largest size that can be bought with `funds` at `price`, fee included. Truncate it to the
+    /// symbol's base increment before placing the order.
+    pub fn max_buy_size(&self, liquidity: Liquidity, price: Decimal, funds: Decimal) -> Result<Decimal> {
+        match funds.checked_div(price * (Decimal::ONE + self.rate(liquidity))) {
+            Some(size) => Ok(size),
+            None => Err(anyhow!("Invalid price: {}", price))?,
+        }
+    }
+}
+
+impl TryFrom<&TradeFee> for FeeRate {
+    type Error = crate::error::Error;
+
+    fn try_from(fee: &TradeFee) -> Result<Self> {
+        let parse = |s: &str| Decimal::from_str(s).map_err(|_| anyhow!("Invalid fee rate: {}", s));
+        Ok(FeeRate { maker: parse(&fee.maker_fee_rate)?, taker: parse(&fee.taker_fee_rate)? })
+    }
+}
+
+/// Per symbol fee rates cached for `ttl`. Cloning yields another handle to the same cache.
+///
+/// Example:
+/// ``` ignore
+/// use kucoin_api::fees::{FeeCache, Liquidity};
+///
+///     let fees = FeeCache::new(Duration::from_secs(3600));
+///     let rate = fees.get(&api, "BTC-USDT").await?;
+///     let estimate = rate.estimate("sell", Liquidity::Taker, Decimal::from(30_000), Decimal::new(1, 1))?;
+/// ```
+#[derive(Clone)]
+pub struct FeeCache {
+    ttl: Duration,
+    rates: Arc<Mutex<HashMap<String, (FeeRate, Instant)>>>,
+}
+
+impl FeeCache {
+    pub fn new(ttl: Duration) -> Self {
+        FeeCache { ttl, rates: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, (FeeRate, Instant)>> {
+        self.rates.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the cached rate of a symbol if it has not expired.
+    pub fn cached(&self, symbol: &str) -> Option<FeeRate> {
+        match self.lock().get(symbol) {
+            Some((rate, at)) if at.elapsed() < self.ttl => Some(*rate),
+            _ => None,
+        }
+    }
+
+    pub fn insert(&self, symbol: &str, rate: FeeRate) {
+        self.lock().insert(symbol.to_string(), (rate, Instant::now()));
+    }
+
+    pub fn invalidate(&self, symbol: &str) {
+        self.lock().remove(symbol);
+    }
+
+    pub async fn get(&self, api: &Kucoin, symbol: &str) -> Result<FeeRate> {
+        let mut rates = self.get_many(api, &[symbol]).await?;
+        match rates.remove(symbol) {
+            Some(r) => Ok(r),
+            None => Err(anyhow!("No fee rate returned for {}", symbol))?,
+        }
+    }
+
+    /// Returns the rates of every symbol, fetching missing or stale ones in batches of
+    /// TRADE_FEES_MAX.
+    pub async fn get_many(&self, api: &Kucoin, symbols: &[&str]) -> Result<HashMap<String, FeeRate>> {
+        let mut rates = HashMap::new();
+        let mut missing = Vec::new();
+        for symbol in symbols {
+            match self.cached(symbol) {
+                Some(r) => {
+                    rates.insert(symbol.to_string(), r);
+                },
+                None => missing.push(*symbol),
+            }
+        }
+        for chunk in missing.chunks(TRADE_FEES_MAX) {
+            let resp = api.get_trade_fees(chunk).await?;
+            let fees = match resp.data {
+                Some(d) => d,
+                None => Err(anyhow!("Failed to get trade fees: {:?}", resp.msg))?,
+            };
+            for fee in &fees {
+                let rate = FeeRate::try_from(fee)?;
+                self.insert(&fee.symbol, rate);
+                rates.insert(fee.symbol.clone(), rate);
+            }
+        }
+        Ok(rates)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryFrom;
+    use std::time::Duration;
+
+    use rust_decimal::Decimal;
+
+    use crate::fees::FeeCache;
+    use crate::fees::FeeRate;
+    use crate::fees::Liquidity;
+    use crate::model::user::TradeFee;
+    use crate::model::APIData;
+
+    #[test]
+    fn estimates_fees_and_caches_rates() {
+        let fees: APIData<TradeFee> = serde_json::from_str(
+            r#"{"code":"200000","data":[{"symbol":"BTC-USDT","takerFeeRate":"0.001","makerFeeRate":"0.0008"}]}"#,
+        )
+        .unwrap();
+        let rate = FeeRate::try_from(&fees.data.unwrap()[0]).unwrap();
+        let price = Decimal::from(20_000);
+        let size = Decimal::new(5, 1);
+        let sell = rate.estimate("sell", Liquidity::Taker, price, size).unwrap();
+        assert_eq!(sell.fee, Decimal::from(10));
+        assert_eq!(sell.net, Decimal::from(9_990));
+        let buy = rate.estimate("buy", Liquidity::Maker, price, size).unwrap();
+        assert_eq!(buy.net, Decimal::from(10_008));
+        assert!(rate.estimate("hold", Liquidity::Maker, Decimal::ONE, Decimal::ONE).is_err());
+        let max = rate.max_buy_size(Liquidity::Taker, Decimal::from(100), Decimal::from(1_001)).unwrap();
+        assert_eq!(max, Decimal::from(10));
+        assert!(rate.max_buy_size(Liquidity::Taker, Decimal::ZERO, Decimal::ONE).is_err());
+        assert_eq!("taker".parse::<Liquidity>().unwrap(), Liquidity::Taker);
+
+        let cache = FeeCache::new(Duration::from_secs(60));
+        cache.insert("BTC-USDT", rate);
+        assert_eq!(cache.cached("BTC-USDT"), Some(rate));
+        let expired = FeeCache::new(Duration::ZERO);
+        expired.insert("BTC-USDT", rate);
+        assert_eq!(expired.cached("BTC-USDT"), None);
+    }
+}
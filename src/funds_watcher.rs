@@ -0,0 +1,410 @@
+//! Deposit and withdrawal tracking
+//!
+//! [`FundsWatcher`] polls `get_deposit_list` and `get_withdrawals_list`, tracks every deposit and
+//! withdrawal through its status transitions and broadcasts a [`FundsEvent`] for each new
+//! record and each status change. Private `/account/balance` websocket events caused by deposits
+//! or withdrawals trigger an immediate poll, so funds are reported as soon as they land instead of
+//! on the next polling tick.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use tokio::sync::broadcast;
+use tokio::sync::Notify;
+
+use crate::client::Kucoin;
+use crate::error::Result;
+use crate::model::user::DepositList;
+use crate::model::user::WithdrawalList;
+use crate::model::websocket::KucoinWebsocketMsg;
+use crate::utils::get_time;
+
+const EVENT_CAPACITY: usize = 256;
+/// Page size requested when polling, the maximum accepted by the endpoints.
+const POLL_PAGE_SIZE: i32 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FundsKind {
+    Deposit,
+    Withdrawal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FundsStatus {
+    Processing,
+    /// Withdrawals only: handed over to the wallet, awaiting the on chain transaction
+    WalletProcessing,
+    Success,
+    Failure,
+}
+
+impl FundsStatus {
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, FundsStatus::Success | FundsStatus::Failure)
+    }
+}
+
+impl FromStr for FundsStatus {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "PROCESSING" => Ok(FundsStatus::Processing),
+            "WALLET_PROCESSING" => Ok(FundsStatus::WalletProcessing),
+            "SUCCESS" => Ok(FundsStatus::Success),
+            "FAILURE" => Ok(FundsStatus::Failure),
+            _ => Err(anyhow!("Invalid deposit/withdrawal status: {}", s))?,
+        }
+    }
+}
+
+/// Identifies a deposit or withdrawal. Deposits have no id of their own, and one wallet
+/// transaction may credit several addresses or memos, so they are keyed by all four.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FundsKey {
+    Deposit { currency: String, address: String, memo: Option<String>, wallet_tx_id: String },
+    Withdrawal(String),
+}
+
+/// Current state of a deposit or withdrawal. `id` is the wallet transaction id of deposits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundsRecord {
+    pub kind: FundsKind,
+    pub id: String,
+    pub currency: String,
+    pub chain: Option<String>,
+    pub address: String,
+    pub memo: Option<String>,
+    pub amount: String,
+    pub fee: String,
+    pub wallet_tx_id: String,
+    pub is_inner: bool,
+    pub status: FundsStatus,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl FundsRecord {
+    pub fn key(&self) -> FundsKey {
+        match self.kind {
+            FundsKind::Deposit => FundsKey::Deposit {
+                currency: self.currency.clone(),
+                address: self.address.clone(),
+                memo: self.memo.clone(),
+                wallet_tx_id: self.wallet_tx_id.clone(),
+            },
+            FundsKind::Withdrawal => FundsKey::Withdrawal(self.id.clone()),
+        }
+    }
+}
+
+impl TryFrom<&DepositList> for FundsRecord {
+    type Error = crate::error::Error;
+
+    fn try_from(d: &DepositList) -> Result<Self> {
+        Ok(FundsRecord {
+            kind: FundsKind::Deposit,
+            id: d.wallet_tx_id.clone(),
+            currency: d.currency.clone(),
+            chain: d.chain.clone(),
+            address: d.address.clone(),
+            memo: Some(d.memo.clone()).filter(|m| !m.is_empty()),
+            amount: d.amount.clone(),
+            fee: d.fee.clone(),
+            wallet_tx_id: d.wallet_tx_id.clone(),
+            is_inner: d.is_inner,
+            status: d.status.parse()?,
+            created_at: d.created_at,
+            updated_at: d.updated_at,
+        })
+    }
+}
+
+impl TryFrom<&WithdrawalList> for FundsRecord {
+    type Error = crate::error::Error;
+
+    fn try_from(w: &WithdrawalList) -> Result<Self> {
+        Ok(FundsRecord {
+            kind: FundsKind::Withdrawal,
+            id: w.id.clone(),
+            currency: w.currency.clone(),
+            chain: w.chain.clone(),
+            address: w.address.clone(),
+            memo: Some(w.memo.clone()).filter(|m| !m.is_empty()),
+            amount: w.amount.clone(),
+            fee: w.fee.clone(),
+            wallet_tx_id: w.wallet_tx_id.clone(),
+            is_inner: w.is_inner,
+            status: w.status.parse()?,
+            created_at: w.created_at,
+            updated_at: w.updated_at,
+        })
+    }
+}
+
+/// A record seen for the first time (`previous` is None) or whose status changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundsEvent {
+    pub previous: Option<FundsStatus>,
+    pub record: FundsRecord,
+}
+
+/// Tracks deposits and withdrawals. Cloning yields another handle to the same watcher.
+///
+/// Example:
+/// ``` ignore
+/// use kucoin_api::funds_watcher::FundsWatcher;
+///
+///     let watcher = FundsWatcher::new();
+///     watcher.seed(&api, Duration::from_secs(86400)).await?;
+///     let mut events = watcher.events();
+///     let poller = watcher.spawn_poll(api.clone(), Duration::from_secs(30))?;
+///
+///     while let Some(msg) = ws.try_next().await? {
+///         watcher.apply(&msg);
+///     }
+/// ```
+#[derive(Clone)]
+pub struct FundsWatcher {
+    records: Arc<Mutex<HashMap<FundsKey, FundsRecord>>>,
+    events: broadcast::Sender<FundsEvent>,
+    wake: Arc<Notify>,
+}
+
+impl Default for FundsWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FundsWatcher {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        FundsWatcher { records: Arc::new(Mutex::new(HashMap::new())), events, wake: Arc::new(Notify::new()) }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<FundsKey, FundsRecord>> {
+        self.records.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn events(&self) -> broadcast::Receiver<FundsEvent> {
+        self.events.subscribe()
+    }
+
+    pub fn get(&self, key: &FundsKey) -> Option<FundsRecord> {
+        self.lock().get(key).cloned()
+    }
+
+    /// Deposits and withdrawals that have not reached a terminal status yet.
+    pub fn pending(&self) -> Vec<FundsRecord> {
+        self.lock().values().filter(|r| !r.status.is_terminal()).cloned().collect()
+    }
+
+    /// Creation time of the oldest pending record of `kind`, if before `start_at`.
+    fn oldest_pending(&self, kind: FundsKind, start_at: i64) -> i64 {
+        self.lock()
+            .values()
+            .filter(|r| r.kind == kind && !r.status.is_terminal())
+            .map(|r| r.created_at)
+            .fold(start_at, i64::min)
+    }
+
+    /// Loads the records of the last `lookback` without emitting events for them.
+    pub async fn seed(&self, api: &Kucoin, lookback: Duration) -> Result<()> {
+        let records = self.fetch(api, lookback).await?;
+        self.apply_records(records);
+        Ok(())
+    }
+
+    /// Polls the records of the last `lookback`, extended back to the oldest pending record, and
+    /// emits an event for each new one and each status change.
+    pub async fn poll(&self, api: &Kucoin, lookback: Duration) -> Result<Vec<FundsEvent>> {
+        let records = self.fetch(api, lookback).await?;
+        let events = self.apply_records(records);
+        for e in &events {
+            tracing::info!(kind = ?e.record.kind, id = %e.record.id, status = ?e.record.status, "Funds status");
+            let _ = self.events.send(e.clone());
+        }
+        Ok(events)
+    }
+
+    async fn fetch(&self, api: &Kucoin, lookback: Duration) -> Result<Vec<FundsRecord>> {
+        let window = get_time().saturating_sub(lookback.as_millis()) as i64;
+        let mut records = Vec::new();
+        let start_at = self.oldest_pending(FundsKind::Deposit, window);
+        let mut page = 1;
+        loop {
+            let resp = api.get_deposit_list(None, Some(start_at), None, None, Some(page), Some(POLL_PAGE_SIZE)).await?;
+            let deposits = match resp.data {
+                Some(d) => d,
+                None => Err(anyhow!("Failed to get deposits: {}", resp.msg.unwrap_or_default()))?,
+            };
+            records.extend(deposits.items.iter().filter_map(|d| parse_record(FundsRecord::try_from(d))));
+            if deposits.current_page >= deposits.total_page {
+                break;
+            }
+            page += 1;
+        }
+        let start_at = self.oldest_pending(FundsKind::Withdrawal, window);
+        let mut page = 1;
+        loop {
+            let resp =
+                api.get_withdrawals_list(None, Some(start_at), None, None, Some(page), Some(POLL_PAGE_SIZE)).await?;
+            let withdrawals = match resp.data {
+                Some(d) => d,
+                None => Err(anyhow!("Failed to get withdrawals: {}", resp.msg.unwrap_or_default()))?,
+            };
+            records.extend(withdrawals.items.iter().filter_map(|w| parse_record(FundsRecord::try_from(w))));
+            if withdrawals.current_page >= withdrawals.total_page {
+                break;
+            }
+            page += 1;
+        }
+        Ok(records)
+    }
+
+    /// Merges records into the watcher and returns the resulting events without broadcasting them.
+    pub fn apply_records(&self, records: Vec<FundsRecord>) -> Vec<FundsEvent> {
+        let mut tracked = self.lock();
+        let mut events = Vec::new();
+        for record in records {
+            let key = record.key();
+            let previous = tracked.get(&key).map(|r| r.status);
+            if previous != Some(record.status) {
+                events.push(FundsEvent { previous, record: record.clone() });
+            }
+            tracked.insert(key, record);
+        }
+        events
+    }
+
+    /// Applies a websocket message: balance changes caused by a deposit or withdrawal wake the
+    /// polling task up. Returns whether the message was such a change.
+    pub fn apply(&self, msg: &KucoinWebsocketMsg) -> bool {
+        match msg {
+            KucoinWebsocketMsg::BalancesMsg(m) if is_funds_event(&m.data.relation_event) => {
+                self.wake.notify_one();
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Spawns a task polling every `every`, or as soon as `apply` saw a deposit or withdrawal.
+    /// Each poll looks back one day, or further for records still pending.
+    pub fn spawn_poll(&self, api: Kucoin, every: Duration) -> Result<tokio::task::JoinHandle<()>> {
+        if every.is_zero() {
+            Err(anyhow!("Polling interval must not be zero"))?
+        }
+        let watcher = self.clone();
+        Ok(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {},
+                    _ = watcher.wake.notified() => {},
+                }
+                if let Err(e) = watcher.poll(&api, Duration::from_secs(86400)).await {
+                    tracing::warn!(%e, "Deposit/withdrawal poll failed");
+                }
+            }
+        }))
+    }
+}
+
+fn parse_record(record: Result<FundsRecord>) -> Option<FundsRecord> {
+    match record {
+        Ok(r) => Some(r),
+        Err(e) => {
+            tracing::warn!(?e, "Skipping deposit/withdrawal record");
+            None
+        },
+    }
+}
+
+/// Balance relation events (e.g. `main.deposit`, `main.withdraw_hold`) caused by funds movements.
+fn is_funds_event(relation_event: &str) -> bool {
+    let event = relation_event.rsplit('.').next().unwrap_or_default();
+    event.starts_with("deposit") || event.starts_with("withdraw")
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryFrom;
+    use std::time::Duration;
+
+    use crate::client::Kucoin;
+    use crate::client::KucoinEnv;
+    use crate::funds_watcher::is_funds_event;
+    use crate::funds_watcher::FundsKind;
+    use crate::funds_watcher::FundsRecord;
+    use crate::funds_watcher::FundsStatus;
+    use crate::funds_watcher::FundsWatcher;
+    use crate::model::user::DepositList;
+    use crate::model::user::WithdrawalList;
+    use crate::model::APIDatum;
+    use crate::model::Pagination;
+
+    const DEPOSITS: &str = r#"{"code":"200000","data":{"currentPage":1,"pageSize":50,"totalNum":2,"totalPage":1,
+        "items":[{"currency":"XRP","chain":"xrp","status":"PROCESSING","address":"rNFugeoj3ZN8Wv6xhuLegUBBPXKCyWLRkB",
+        "memo":"1919537769","isInner":false,"amount":"20.50000000","fee":"0.00000000",
+        "walletTxId":"2C24A6D5B3E7D5B6AA6534025B9B107AC910309A98825BF5581E25BEC94AD83B",
+        "createdAt":1666600519000,"updatedAt":1666600519000,"remark":"Deposit"},
+        {"currency":"XRP","chain":"xrp","status":"PROCESSING","address":"rNFugeoj3ZN8Wv6xhuLegUBBPXKCyWLRkB",
+        "memo":"2024771133","isInner":false,"amount":"3.00000000","fee":"0.00000000",
+        "walletTxId":"2C24A6D5B3E7D5B6AA6534025B9B107AC910309A98825BF5581E25BEC94AD83B",
+        "createdAt":1666600519000,"updatedAt":1666600519000,"remark":"Deposit"}]}}"#;
+
+    const WITHDRAWALS: &str = r#"{"code":"200000","data":{"currentPage":1,"pageSize":50,"totalNum":1,"totalPage":1,
+        "items":[{"id":"63564dbbd17bef00019371fb","currency":"XRP","chain":"xrp","status":"PROCESSING",
+        "address":"rNFugeoj3ZN8Wv6xhuLegUBBPXKCyWLRkB","memo":"1919537769","isInner":false,"amount":"20.50000000",
+        "fee":"0.50000000","walletTxId":"","createdAt":1666600379000,"updatedAt":1666600379000,
+        "remark":"test"}]}}"#;
+
+    fn records(deposits: &str, withdrawals: &str) -> Vec<FundsRecord> {
+        let deposits: APIDatum<Pagination<DepositList>> = serde_json::from_str(deposits).unwrap();
+        let withdrawals: APIDatum<Pagination<WithdrawalList>> = serde_json::from_str(withdrawals).unwrap();
+        let mut records: Vec<FundsRecord> =
+            deposits.data.unwrap().items.iter().map(|d| FundsRecord::try_from(d).unwrap()).collect();
+        records.extend(withdrawals.data.unwrap().items.iter().map(|w| FundsRecord::try_from(w).unwrap()));
+        records
+    }
+
+    #[test]
+    fn tracks_status_transitions() {
+        let watcher = FundsWatcher::new();
+        let events = watcher.apply_records(records(DEPOSITS, WITHDRAWALS));
+        assert_eq!(events.len(), 3);
+        assert_eq!(watcher.pending().len(), 3);
+        assert_eq!(watcher.oldest_pending(FundsKind::Withdrawal, i64::MAX), 1666600379000);
+        assert_eq!(watcher.oldest_pending(FundsKind::Deposit, 1), 1);
+
+        let deposits = DEPOSITS.replacen(r#""status":"PROCESSING""#, r#""status":"SUCCESS""#, 1);
+        let events = watcher.apply_records(records(&deposits, WITHDRAWALS));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].previous, Some(FundsStatus::Processing));
+        assert_eq!(events[0].record.status, FundsStatus::Success);
+        assert_eq!(events[0].record.memo.as_deref(), Some("1919537769"));
+        assert_eq!(watcher.get(&events[0].record.key()).unwrap().status, FundsStatus::Success);
+        assert_eq!(watcher.pending().len(), 2);
+
+        assert_eq!("WALLET_PROCESSING".parse::<FundsStatus>().unwrap(), FundsStatus::WalletProcessing);
+        assert!("DONE".parse::<FundsStatus>().is_err());
+        assert!(is_funds_event("main.deposit"));
+        assert!(is_funds_event("main.withdraw_hold"));
+        assert!(!is_funds_event("trade.hold"));
+    }
+
+    #[tokio::test]
+    async fn rejects_a_zero_poll_interval() {
+        let api = Kucoin::new(KucoinEnv::Sandbox, None).unwrap();
+        assert!(FundsWatcher::new().spawn_poll(api, Duration::ZERO).is_err());
+    }
+}
@@ -0,0 +1,619 @@
+//! Kucoin Futures REST client
+//!
+//! Futures live on their own host (`api-futures.kucoin.com`) but share the spot API key scheme.
+//! [`KucoinFutures`] wraps a [`Kucoin`] client pointed at the futures host, so requests are signed
+//! exactly like spot requests and the same credentials can be reused. The module is not named
+//! `futures` to avoid clashing with the re-exported `futures` crate.
+//!
+//! Example:
+//! ``` ignore
+//! use kucoin_api::client::{Credentials, Kucoin, KucoinEnv};
+//! use kucoin_api::futures_api::KucoinFutures;
+//!
+//!     let api = Kucoin::new(KucoinEnv::Live, Some(credentials))?;
+//!     let futures = KucoinFutures::from_spot(&api, KucoinEnv::Live);
+//!     let overview = futures.get_account_overview(Some("USDT")).await?;
+//! ```
+
+use std::collections::HashMap;
+
+use reqwest::header;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::client::Credentials;
+use crate::client::Kucoin;
+use crate::client::KucoinEnv;
+use crate::error::Result;
+use crate::model::futures::AccountOverview;
+use crate::model::futures::Contract;
+use crate::model::futures::CrossLeverage;
+use crate::model::futures::FundingHistory;
+use crate::model::futures::FuturesCancelByClientOidResp;
+use crate::model::futures::FuturesCancelResp;
+use crate::model::futures::FuturesCandle;
+use crate::model::futures::FuturesFill;
+use crate::model::futures::FuturesMarginMode;
+use crate::model::futures::FuturesOrder;
+use crate::model::futures::FuturesOrderBook;
+use crate::model::futures::FuturesOrderResp;
+use crate::model::futures::FuturesTicker;
+use crate::model::futures::MarginModeResp;
+use crate::model::futures::Position;
+use crate::model::parameters::klines::Klines;
+use crate::model::websocket::WSType;
+use crate::model::APIData;
+use crate::model::APIDatum;
+use crate::model::Method;
+use crate::model::Pagination;
+use crate::utils::format_query;
+use crate::websocket::KucoinWebsocket;
+
+pub fn futures_prefix(environment: KucoinEnv) -> &'static str {
+    match environment {
+        KucoinEnv::Live => "https://api-futures.kucoin.com",
+        KucoinEnv::Sandbox => "https://api-sandbox-futures.kucoin.com",
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KucoinFutures {
+    api: Kucoin,
+}
+
+impl KucoinFutures {
+    pub fn new(environment: KucoinEnv, credentials: Option<Credentials>) -> Result<Self> {
+        let mut api = Kucoin::new(environment, credentials)?;
+        api.prefix = futures_prefix(environment).to_string();
+        Ok(KucoinFutures { api })
+    }
+
+    /// Builds a futures client sharing the credentials and connection pool of a spot client.
+    pub fn from_spot(api: &Kucoin, environment: KucoinEnv) -> Self {
+        let mut api = api.clone();
+        api.prefix = futures_prefix(environment).to_string();
+        KucoinFutures { api }
+    }
+
+    /// The underlying client, pointed at the futures host.
+    pub fn api(&self) -> &Kucoin {
+        &self.api
+    }
+
+    /// See [`Kucoin::set_dry_run`], orders are sent to the futures order test endpoint.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.api.set_dry_run(dry_run);
+    }
+
+    pub fn websocket(&self) -> KucoinWebsocket {
+        self.api.websocket()
+    }
+
+    /// Requests a futures bullet token and returns the socket url to subscribe futures topics on.
+    pub async fn get_socket_endpoint(&self, ws_type: WSType) -> Result<String> {
+        self.api.get_socket_endpoint(ws_type).await
+    }
+
+    pub async fn get_active_contracts(&self) -> Result<APIData<Contract>> {
+        self.public(String::from("/api/v1/contracts/active"), HashMap::new()).await
+    }
+
+    pub async fn get_contract(&self, symbol: &str) -> Result<APIDatum<Contract>> {
+        self.public(format!("/api/v1/contracts/{}", symbol), HashMap::new()).await
+    }
+
+    pub async fn get_ticker(&self, symbol: &str) -> Result<APIDatum<FuturesTicker>> {
+        self.public(String::from("/api/v1/ticker"), symbol_param(symbol)).await
+    }
+
+    /// Full level 2 order book snapshot.
+    pub async fn get_orderbook(&self, symbol: &str) -> Result<APIDatum<FuturesOrderBook>> {
+        self.public(String::from("/api/v1/level2/snapshot"), symbol_param(symbol)).await
+    }
+
+    /// Candles between `from` and `to` (milliseconds). Futures do not offer 3min and 6hour
+    /// candles, requesting them returns an API error.
+    pub async fn get_klines(
+        &self,
+        symbol: &str,
+        granularity: Klines,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> Result<APIData<FuturesCandle>> {
+        let mut params = symbol_param(symbol);
+        params.insert(String::from("granularity"), (granularity.as_seconds() / 60).to_string());
+        if let Some(f) = from {
+            params.insert(String::from("from"), f.to_string());
+        }
+        if let Some(t) = to {
+            params.insert(String::from("to"), t.to_string());
+        }
+        self.public(String::from("/api/v1/kline/query"), params).await
+    }
+
+    pub async fn get_positions(&self) -> Result<APIData<Position>> {
+        self.signed(String::from("/api/v1/positions"), HashMap::new(), Method::GET).await
+    }
+
+    pub async fn get_position(&self, symbol: &str) -> Result<APIDatum<Position>> {
+        self.signed(String::from("/api/v1/position"), symbol_param(symbol), Method::GET).await
+    }
+
+    pub async fn get_margin_mode(&self, symbol: &str) -> Result<APIDatum<MarginModeResp>> {
+        self.signed(String::from("/api/v2/position/getMarginMode"), symbol_param(symbol), Method::GET).await
+    }
+
+    pub async fn set_margin_mode(&self, symbol: &str, mode: FuturesMarginMode) -> Result<APIDatum<MarginModeResp>> {
+        let body = json!({ "symbol": symbol, "marginMode": mode.as_str() });
+        self.post(String::from("/api/v2/position/changeMarginMode"), body).await
+    }
+
+    /// Leverage used for cross margin positions of a symbol.
+    pub async fn get_cross_leverage(&self, symbol: &str) -> Result<APIDatum<CrossLeverage>> {
+        self.signed(String::from("/api/v2/getCrossUserLeverage"), symbol_param(symbol), Method::GET).await
+    }
+
+    pub async fn set_cross_leverage(&self, symbol: &str, leverage: &str) -> Result<APIDatum<bool>> {
+        let body = json!({ "symbol": symbol, "leverage": leverage });
+        self.post(String::from("/api/v2/changeCrossUserLeverage"), body).await
+    }
+
+    /// Places a futures limit order. `size` is in lots, see the contract multiplier. Isolated
+    /// margin orders use `leverage`, cross margin orders the symbol's cross leverage.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn post_limit_order(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        leverage: &str,
+        price: &str,
+        size: u64,
+        optionals: Option<FuturesOrderOptionals<'_>>,
+    ) -> Result<APIDatum<FuturesOrderResp>> {
+        let mut body = order_body(client_oid, symbol, side, leverage, size, optionals);
+        body["type"] = json!("limit");
+        body["price"] = json!(price);
+        self.post(self.api.order_endpoint("/api/v1/orders", &body), body).await
+    }
+
+    pub async fn post_market_order(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        leverage: &str,
+        size: u64,
+        optionals: Option<FuturesOrderOptionals<'_>>,
+    ) -> Result<APIDatum<FuturesOrderResp>> {
+        let mut body = order_body(client_oid, symbol, side, leverage, size, optionals);
+        body["type"] = json!("market");
+        self.post(self.api.order_endpoint("/api/v1/orders", &body), body).await
+    }
+
+    pub async fn cancel_order(&self, order_id: &str) -> Result<APIDatum<FuturesCancelResp>> {
+        self.signed(format!("/api/v1/orders/{}", order_id), HashMap::new(), Method::DELETE).await
+    }
+
+    pub async fn cancel_order_by_client_oid(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+    ) -> Result<APIDatum<FuturesCancelByClientOidResp>> {
+        let endpoint = format!("/api/v1/orders/client-order/{}", client_oid);
+        self.signed(endpoint, symbol_param(symbol), Method::DELETE).await
+    }
+
+    /// Cancels every open order, optionally limited to a symbol.
+    pub async fn cancel_all_orders(&self, symbol: Option<&str>) -> Result<APIDatum<FuturesCancelResp>> {
+        let params = symbol.map(symbol_param).unwrap_or_default();
+        self.signed(String::from("/api/v1/orders"), params, Method::DELETE).await
+    }
+
+    /// Lists orders, `status` is either active or done. See FuturesListOptionals for the filters.
+    pub async fn get_orders(
+        &self,
+        status: Option<&str>,
+        optionals: Option<FuturesListOptionals<'_>>,
+    ) -> Result<APIDatum<Pagination<FuturesOrder>>> {
+        let mut params = optionals.unwrap_or_default().to_params();
+        if let Some(s) = status {
+            params.insert(String::from("status"), s.to_string());
+        }
+        self.signed(String::from("/api/v1/orders"), params, Method::GET).await
+    }
+
+    pub async fn get_order(&self, order_id: &str) -> Result<APIDatum<FuturesOrder>> {
+        self.signed(format!("/api/v1/orders/{}", order_id), HashMap::new(), Method::GET).await
+    }
+
+    pub async fn get_order_by_client_oid(&self, client_oid: &str) -> Result<APIDatum<FuturesOrder>> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("clientOid"), client_oid.to_string());
+        self.signed(String::from("/api/v1/orders/byClientOid"), params, Method::GET).await
+    }
+
+    pub async fn get_fills(
+        &self,
+        order_id: Option<&str>,
+        optionals: Option<FuturesListOptionals<'_>>,
+    ) -> Result<APIDatum<Pagination<FuturesFill>>> {
+        let mut params = optionals.unwrap_or_default().to_params();
+        if let Some(o) = order_id {
+            params.insert(String::from("orderId"), o.to_string());
+        }
+        self.signed(String::from("/api/v1/fills"), params, Method::GET).await
+    }
+
+    /// Funding settlements of a symbol between `start_at` and `end_at` (milliseconds), at most
+    /// `max_count` per page. Page with `offset` using the id of the last record returned.
+    pub async fn get_funding_history(
+        &self,
+        symbol: &str,
+        start_at: Option<i64>,
+        end_at: Option<i64>,
+        offset: Option<i64>,
+        max_count: Option<i32>,
+    ) -> Result<APIDatum<FundingHistory>> {
+        let mut params = symbol_param(symbol);
+        if let Some(s) = start_at {
+            params.insert(String::from("startAt"), s.to_string());
+        }
+        if let Some(e) = end_at {
+            params.insert(String::from("endAt"), e.to_string());
+        }
+        if let Some(o) = offset {
+            params.insert(String::from("offset"), o.to_string());
+        }
+        if let Some(m) = max_count {
+            params.insert(String::from("maxCount"), m.to_string());
+        }
+        self.signed(String::from("/api/v1/funding-history"), params, Method::GET).await
+    }
+
+    /// Account overview in `currency`, XBT by default.
+    pub async fn get_account_overview(&self, currency: Option<&str>) -> Result<APIDatum<AccountOverview>> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        if let Some(c) = currency {
+            params.insert(String::from("currency"), c.to_string());
+        }
+        self.signed(String::from("/api/v1/account-overview"), params, Method::GET).await
+    }
+
+    async fn public<T: DeserializeOwned>(&self, endpoint: String, params: HashMap<String, String>) -> Result<T> {
+        let url = format!("{}{}{}", &self.api.prefix, endpoint, format_query(&params));
+        let resp = self.api.get(url, None).await?.json().await?;
+        Ok(resp)
+    }
+
+    async fn signed<T: DeserializeOwned>(
+        &self,
+        endpoint: String,
+        params: HashMap<String, String>,
+        method: Method,
+    ) -> Result<T> {
+        let query = format_query(&params);
+        let url = format!("{}{}{}", &self.api.prefix, endpoint, query);
+        let resp = match method {
+            Method::DELETE => {
+                let headers: header::HeaderMap = self.api.sign_headers(endpoint, None, Some(query), Method::DELETE)?;
+                self.api.delete(url, Some(headers)).await?
+            },
+            _ => {
+                let headers: header::HeaderMap = self.api.sign_headers(endpoint, None, Some(query), Method::GET)?;
+                self.api.get(url, Some(headers)).await?
+            },
+        };
+        Ok(resp.json().await?)
+    }
+
+    async fn post<T: DeserializeOwned>(&self, endpoint: String, body: Value) -> Result<T> {
+        let url = format!("{}{}", &self.api.prefix, endpoint);
+        let body = body.to_string();
+        let headers: header::HeaderMap = self.api.sign_headers_json(endpoint, &body, Method::POST)?;
+        let resp = self.api.post_json(url, Some(headers), body).await?.json().await?;
+        Ok(resp)
+    }
+}
+
+fn symbol_param(symbol: &str) -> HashMap<String, String> {
+    let mut params: HashMap<String, String> = HashMap::new();
+    params.insert(String::from("symbol"), symbol.to_string());
+    params
+}
+
+fn order_body(
+    client_oid: &str,
+    symbol: &str,
+    side: &str,
+    leverage: &str,
+    size: u64,
+    optionals: Option<FuturesOrderOptionals<'_>>,
+) -> Value {
+    let mut body = json!({
+        "clientOid": client_oid,
+        "symbol": symbol,
+        "side": side,
+        "leverage": leverage,
+        "size": size,
+    });
+    if let Some(o) = optionals {
+        if let Some(m) = o.margin_mode {
+            body["marginMode"] = json!(m.as_str());
+        }
+        if let Some(r) = o.remark {
+            body["remark"] = json!(r);
+        }
+        if let Some(s) = o.stop {
+            body["stop"] = json!(s);
+        }
+        if let Some(s) = o.stop_price_type {
+            body["stopPriceType"] = json!(s);
+        }
+        if let Some(s) = o.stop_price {
+            body["stopPrice"] = json!(s);
+        }
+        if let Some(r) = o.reduce_only {
+            body["reduceOnly"] = json!(r);
+        }
+        if let Some(c) = o.close_order {
+            body["closeOrder"] = json!(c);
+        }
+        if let Some(t) = o.time_in_force {
+            body["timeInForce"] = json!(t);
+        }
+        if let Some(p) = o.post_only {
+            body["postOnly"] = json!(p);
+        }
+        if let Some(h) = o.hidden {
+            body["hidden"] = json!(h);
+        }
+        if let Some(i) = o.iceberg {
+            body["iceberg"] = json!(i);
+        }
+        if let Some(v) = o.visible_size {
+            body["visibleSize"] = json!(v);
+        }
+    }
+    body
+}
+
+/// FuturesOrderOptionals contains a builder pattern for the optional inputs of futures orders.
+///
+/// Example:
+/// ``` rust
+/// use kucoin_api::futures_api::FuturesOrderOptionals;
+/// use kucoin_api::model::futures::FuturesMarginMode;
+///
+///     let options = FuturesOrderOptionals::new()
+///         .margin_mode(FuturesMarginMode::Cross)
+///         .reduce_only(true)
+///         .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FuturesOrderOptionals<'a> {
+    pub margin_mode: Option<FuturesMarginMode>,
+    pub remark: Option<&'a str>,
+    pub stop: Option<&'a str>,
+    pub stop_price_type: Option<&'a str>,
+    pub stop_price: Option<&'a str>,
+    pub reduce_only: Option<bool>,
+    pub close_order: Option<bool>,
+    pub time_in_force: Option<&'a str>,
+    pub post_only: Option<bool>,
+    pub hidden: Option<bool>,
+    pub iceberg: Option<bool>,
+    pub visible_size: Option<u64>,
+}
+
+impl<'a> FuturesOrderOptionals<'a> {
+    pub fn new() -> Self {
+        FuturesOrderOptionals {
+            margin_mode: None,
+            remark: None,
+            stop: None,
+            stop_price_type: None,
+            stop_price: None,
+            reduce_only: None,
+            close_order: None,
+            time_in_force: None,
+            post_only: None,
+            hidden: None,
+            iceberg: None,
+            visible_size: None,
+        }
+    }
+
+    pub fn margin_mode(&mut self, m: FuturesMarginMode) -> &mut Self {
+        self.margin_mode = Some(m);
+        self
+    }
+
+    pub fn remark(&mut self, r: &'a str) -> &mut Self {
+        self.remark = Some(r);
+        self
+    }
+
+    /// Either `down` or `up`, requires `stop_price_type` and `stop_price`.
+    pub fn stop(&mut self, s: &'a str) -> &mut Self {
+        self.stop = Some(s);
+        self
+    }
+
+    /// One of TP (last trade price), MP (mark price) or IP (index price).
+    pub fn stop_price_type(&mut self, s: &'a str) -> &mut Self {
+        self.stop_price_type = Some(s);
+        self
+    }
+
+    pub fn stop_price(&mut self, s: &'a str) -> &mut Self {
+        self.stop_price = Some(s);
+        self
+    }
+
+    pub fn reduce_only(&mut self, r: bool) -> &mut Self {
+        self.reduce_only = Some(r);
+        self
+    }
+
+    pub fn close_order(&mut self, c: bool) -> &mut Self {
+        self.close_order = Some(c);
+        self
+    }
+
+    pub fn time_in_force(&mut self, t: &'a str) -> &mut Self {
+        self.time_in_force = Some(t);
+        self
+    }
+
+    pub fn post_only(&mut self, p: bool) -> &mut Self {
+        self.post_only = Some(p);
+        self
+    }
+
+    pub fn hidden(&mut self, h: bool) -> &mut Self {
+        self.hidden = Some(h);
+        self
+    }
+
+    pub fn iceberg(&mut self, i: bool) -> &mut Self {
+        self.iceberg = Some(i);
+        self
+    }
+
+    pub fn visible_size(&mut self, v: u64) -> &mut Self {
+        self.visible_size = Some(v);
+        self
+    }
+
+    pub fn build(&self) -> Self {
+        self.clone()
+    }
+}
+
+/// FuturesListOptionals contains a builder pattern for the filters of get_orders and get_fills.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FuturesListOptionals<'a> {
+    pub symbol: Option<&'a str>,
+    pub side: Option<&'a str>,
+    pub r#type: Option<&'a str>,
+    pub start_at: Option<i64>,
+    pub end_at: Option<i64>,
+    pub current_page: Option<i32>,
+    pub page_size: Option<i32>,
+}
+
+impl<'a> FuturesListOptionals<'a> {
+    pub fn new() -> Self {
+        FuturesListOptionals {
+            symbol: None,
+            side: None,
+            r#type: None,
+            start_at: None,
+            end_at: None,
+            current_page: None,
+            page_size: None,
+        }
+    }
+
+    pub fn symbol(&mut self, s: &'a str) -> &mut Self {
+        self.symbol = Some(s);
+        self
+    }
+
+    pub fn side(&mut self, s: &'a str) -> &mut Self {
+        self.side = Some(s);
+        self
+    }
+
+    pub fn order_type(&mut self, s: &'a str) -> &mut Self {
+        self.r#type = Some(s);
+        self
+    }
+
+    pub fn start_at(&mut self, i: i64) -> &mut Self {
+        self.start_at = Some(i);
+        self
+    }
+
+    pub fn end_at(&mut self, i: i64) -> &mut Self {
+        self.end_at = Some(i);
+        self
+    }
+
+    pub fn current_page(&mut self, i: i32) -> &mut Self {
+        self.current_page = Some(i);
+        self
+    }
+
+    pub fn page_size(&mut self, i: i32) -> &mut Self {
+        self.page_size = Some(i);
+        self
+    }
+
+    pub fn build(&self) -> Self {
+        self.clone()
+    }
+
+    fn to_params(&self) -> HashMap<String, String> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        if let Some(o) = self.symbol {
+            params.insert("symbol".to_string(), o.to_string());
+        }
+        if let Some(o) = self.side {
+            params.insert("side".to_string(), o.to_string());
+        }
+        if let Some(o) = self.r#type {
+            params.insert("type".to_string(), o.to_string());
+        }
+        if let Some(o) = self.start_at {
+            params.insert("startAt".to_string(), o.to_string());
+        }
+        if let Some(o) = self.end_at {
+            params.insert("endAt".to_string(), o.to_string());
+        }
+        if let Some(o) = self.current_page {
+            params.insert("currentPage".to_string(), o.to_string());
+        }
+        if let Some(o) = self.page_size {
+            params.insert("pageSize".to_string(), o.to_string());
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::client::Kucoin;
+    use crate::client::KucoinEnv;
+    use crate::futures_api::order_body;
+    use crate::futures_api::FuturesOrderOptionals;
+    use crate::futures_api::KucoinFutures;
+    use crate::model::futures::FuturesMarginMode;
+
+    #[test]
+    fn shares_spot_client_with_futures_host() {
+        let api = Kucoin::new(KucoinEnv::Sandbox, None).unwrap();
+        let futures = KucoinFutures::from_spot(&api, KucoinEnv::Sandbox);
+        assert_eq!(futures.api().prefix, "https://api-sandbox-futures.kucoin.com");
+        assert_eq!(api.prefix, "https://openapi-sandbox.kucoin.com");
+    }
+
+    #[test]
+    fn order_body_uses_typed_values() {
+        let opts = FuturesOrderOptionals::new()
+            .margin_mode(FuturesMarginMode::Isolated)
+            .reduce_only(true)
+            .stop("down")
+            .stop_price_type("MP")
+            .stop_price("25000")
+            .build();
+        let body = order_body("oid", "XBTUSDTM", "sell", "5", 3, Some(opts));
+        assert_eq!(body["size"], 3);
+        assert_eq!(body["leverage"], "5");
+        assert_eq!(body["reduceOnly"], true);
+        assert_eq!(body["marginMode"], "ISOLATED");
+        assert_eq!(body["stopPriceType"], "MP");
+    }
+}
@@ -247,15 +247,28 @@ pub use url;
 #[macro_use]
 pub extern crate serde_derive;
 
+pub mod balance_book;
+pub mod candle;
 /// Main Kucoin API Client w/ All Endpoints
 pub mod client;
+pub mod currency_registry;
+pub mod dead_mans_switch;
 pub mod error;
+pub mod fees;
+pub mod funds_watcher;
+pub mod futures_api;
 pub mod margin;
 pub mod market;
 /// API Response Strucs
 pub mod model;
+pub mod oco;
+pub mod order_manager;
+pub mod recorder;
 pub mod trade;
+pub mod trade_hf;
 pub mod user;
 /// Utility Functions
 pub mod utils;
 pub mod websocket;
+pub mod withdrawal;
+pub mod withdrawal_guard;
@@ -1,18 +1,37 @@
 use std::collections::HashMap;
 
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use serde_json::Value;
+
 use super::client::Kucoin;
 use super::model::margin::BorrowOrder;
 use super::model::margin::BorrowOrderId;
+use super::model::margin::IsolatedAccount;
+use super::model::margin::IsolatedAccounts;
+use super::model::margin::IsolatedBorrowResp;
 use super::model::margin::LendHistory;
 use super::model::margin::LendMarketData;
 use super::model::margin::LendOrder;
 use super::model::margin::LendRecord;
+use super::model::margin::LendingCurrency;
+use super::model::margin::LendingMarketRate;
+use super::model::margin::LendingOrderNo;
+use super::model::margin::LendingPurchaseOrder;
+use super::model::margin::LendingRedeemOrder;
 use super::model::margin::MarginAccounts;
+use super::model::margin::MarginBorrowRecord;
+use super::model::margin::MarginBorrowResp;
 use super::model::margin::MarginHistory;
 use super::model::margin::MarginInfo;
+use super::model::margin::MarginInterestRecord;
+use super::model::margin::MarginModel;
 use super::model::margin::MarginOrder;
 use super::model::margin::MarginOrderId;
+use super::model::margin::MarginRepayRecord;
+use super::model::margin::MarginRepayResp;
 use super::model::margin::MarginTradeData;
+use super::model::margin::MarginTradeOrderResp;
 use super::model::margin::MarkPrice;
 use super::model::margin::RepayRecord;
 use super::model::margin::RepaymentRecord;
@@ -20,6 +39,8 @@ use super::model::APIData;
 use super::model::APIDatum;
 use super::model::Method;
 use super::model::Pagination;
+use super::trade::parse_order;
+use super::trade::OrderOptionals;
 use super::utils::format_query;
 use crate::error::Result;
 
@@ -329,4 +350,622 @@ impl Kucoin {
         let resp = self.get(url, Some(headers)).await?.json().await?;
         Ok(resp)
     }
+
+    /// Places a margin limit order. See MarginOrderOptionals for the margin model and auto
+    /// borrow/repay settings, defaults to cross margin without either, and OrderOptionals for the
+    /// remaining optional inputs.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn post_margin_limit_order(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        price: &str,
+        size: &str,
+        margin: Option<MarginOrderOptionals>,
+        optionals: Option<OrderOptionals<'_>>,
+    ) -> Result<APIDatum<MarginTradeOrderResp>> {
+        let params = margin_limit_params(client_oid, symbol, side, price, size, margin, optionals);
+        self.post_margin_order(params).await
+    }
+
+    /// Places a margin market order. Only one of size (base currency) or funds (quote currency)
+    /// should be given.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn post_margin_market_order(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        size: Option<&str>,
+        funds: Option<&str>,
+        margin: Option<MarginOrderOptionals>,
+        optionals: Option<OrderOptionals<'_>>,
+    ) -> Result<APIDatum<MarginTradeOrderResp>> {
+        let params = margin_market_params(client_oid, symbol, side, size, funds, margin, optionals);
+        self.post_margin_order(params).await
+    }
+
+    async fn post_margin_order(&self, params: HashMap<String, String>) -> Result<APIDatum<MarginTradeOrderResp>> {
+        let endpoint = self.order_endpoint("/api/v1/margin/order", &params);
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Returns every isolated margin account. `balance_currency` selects the currency the totals
+    /// are converted to, USDT by default.
+    pub async fn get_isolated_accounts(&self, balance_currency: Option<&str>) -> Result<APIDatum<IsolatedAccounts>> {
+        let mut endpoint = String::from("/api/v1/isolated/accounts");
+        if let Some(c) = balance_currency {
+            endpoint.push_str(&format!("?balanceCurrency={}", c));
+        }
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers = self.sign_headers(endpoint, None, None, Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    pub async fn get_isolated_account(&self, symbol: &str) -> Result<APIDatum<IsolatedAccount>> {
+        let endpoint = format!("/api/v1/isolated/account/{}", symbol);
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers = self.sign_headers(endpoint, None, None, Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Borrows on an isolated margin pair. `borrow_strategy` is either FOK or IOC and `period`
+    /// a comma delimited list of terms in days (7,14,28).
+    pub async fn post_isolated_borrow(
+        &self,
+        symbol: &str,
+        currency: &str,
+        size: &str,
+        borrow_strategy: &str,
+        max_rate: Option<&str>,
+        period: Option<&str>,
+    ) -> Result<APIDatum<IsolatedBorrowResp>> {
+        let endpoint = String::from("/api/v1/isolated/borrow");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let params = isolated_borrow_params(symbol, currency, size, borrow_strategy, max_rate, period);
+        let headers = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Repays isolated margin liabilities in bulk. `seq_strategy` is either
+    /// RECENTLY_EXPIRE_FIRST or HIGHEST_RATE_FIRST.
+    pub async fn isolated_repay_all(
+        &self,
+        symbol: &str,
+        currency: &str,
+        size: &str,
+        seq_strategy: &str,
+    ) -> Result<APIDatum<String>> {
+        let endpoint = String::from("/api/v1/isolated/repay/all");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("symbol"), symbol.to_string());
+        params.insert(String::from("currency"), currency.to_string());
+        params.insert(String::from("size"), size.to_string());
+        params.insert(String::from("seqStrategy"), seq_strategy.to_string());
+        let headers = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
+        Ok(resp)
+    }
+
+    pub async fn isolated_repay_single(
+        &self,
+        symbol: &str,
+        currency: &str,
+        size: &str,
+        loan_id: &str,
+    ) -> Result<APIDatum<String>> {
+        let endpoint = String::from("/api/v1/isolated/repay/single");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("symbol"), symbol.to_string());
+        params.insert(String::from("currency"), currency.to_string());
+        params.insert(String::from("size"), size.to_string());
+        params.insert(String::from("loanId"), loan_id.to_string());
+        let headers = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Borrows through the v3 margin endpoint. Pass `isolated_symbol` to borrow on an isolated
+    /// margin pair, cross margin otherwise. `time_in_force` is either IOC or FOK and `is_hf`
+    /// selects the HF margin account.
+    pub async fn post_margin_borrow(
+        &self,
+        currency: &str,
+        size: &str,
+        time_in_force: &str,
+        isolated_symbol: Option<&str>,
+        is_hf: bool,
+    ) -> Result<APIDatum<MarginBorrowResp>> {
+        let mut body = margin_loan_body(currency, size, isolated_symbol, is_hf);
+        body["timeInForce"] = json!(time_in_force);
+        self.post_margin_v3(String::from("/api/v3/margin/borrow"), body).await
+    }
+
+    /// Repays through the v3 margin endpoint, see post_margin_borrow for the inputs.
+    pub async fn post_margin_repay(
+        &self,
+        currency: &str,
+        size: &str,
+        isolated_symbol: Option<&str>,
+        is_hf: bool,
+    ) -> Result<APIDatum<MarginRepayResp>> {
+        let body = margin_loan_body(currency, size, isolated_symbol, is_hf);
+        self.post_margin_v3(String::from("/api/v3/margin/repay"), body).await
+    }
+
+    pub async fn get_margin_borrow_history(
+        &self,
+        currency: &str,
+        optionals: Option<MarginHistoryOptionals<'_>>,
+    ) -> Result<APIDatum<Pagination<MarginBorrowRecord>>> {
+        let mut params = optionals.unwrap_or_default().to_params();
+        params.insert(String::from("currency"), currency.to_string());
+        self.get_margin_v3(String::from("/api/v3/margin/borrow"), params).await
+    }
+
+    pub async fn get_margin_repay_history(
+        &self,
+        currency: &str,
+        optionals: Option<MarginHistoryOptionals<'_>>,
+    ) -> Result<APIDatum<Pagination<MarginRepayRecord>>> {
+        let mut params = optionals.unwrap_or_default().to_params();
+        params.insert(String::from("currency"), currency.to_string());
+        self.get_margin_v3(String::from("/api/v3/margin/repay"), params).await
+    }
+
+    pub async fn get_margin_interest_history(
+        &self,
+        currency: Option<&str>,
+        optionals: Option<MarginHistoryOptionals<'_>>,
+    ) -> Result<APIDatum<Pagination<MarginInterestRecord>>> {
+        let mut params = optionals.unwrap_or_default().to_params();
+        if let Some(c) = currency {
+            params.insert(String::from("currency"), c.to_string());
+        }
+        self.get_margin_v3(String::from("/api/v3/margin/interest"), params).await
+    }
+
+    /// Lists the currencies available on the lending market, with their size and rate limits.
+    pub async fn get_lending_currencies(&self, currency: Option<&str>) -> Result<APIData<LendingCurrency>> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        if let Some(c) = currency {
+            params.insert(String::from("currency"), c.to_string());
+        }
+        self.get_margin_v3(String::from("/api/v3/project/list"), params).await
+    }
+
+    pub async fn get_lending_market_rate(&self, currency: &str) -> Result<APIData<LendingMarketRate>> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("currency"), currency.to_string());
+        self.get_margin_v3(String::from("/api/v3/project/marketInterestRate"), params).await
+    }
+
+    /// Subscribes `size` of `currency` to the lending market at the minimum hourly
+    /// `interest_rate`.
+    pub async fn post_lending_purchase(
+        &self,
+        currency: &str,
+        size: &str,
+        interest_rate: &str,
+    ) -> Result<APIDatum<LendingOrderNo>> {
+        let body = json!({ "currency": currency, "size": size, "interestRate": interest_rate });
+        self.post_margin_v3(String::from("/api/v3/purchase"), body).await
+    }
+
+    /// Redeems `size` from the purchase order `purchase_order_no`.
+    pub async fn post_lending_redeem(
+        &self,
+        currency: &str,
+        size: &str,
+        purchase_order_no: &str,
+    ) -> Result<APIDatum<LendingOrderNo>> {
+        let body = json!({ "currency": currency, "size": size, "purchaseOrderNo": purchase_order_no });
+        self.post_margin_v3(String::from("/api/v3/redeem"), body).await
+    }
+
+    /// Updates the minimum interest rate of a purchase order, applied from the next hour.
+    pub async fn update_lending_purchase(
+        &self,
+        currency: &str,
+        interest_rate: &str,
+        purchase_order_no: &str,
+    ) -> Result<APIDatum<String>> {
+        let body = json!({ "currency": currency, "interestRate": interest_rate, "purchaseOrderNo": purchase_order_no });
+        self.post_margin_v3(String::from("/api/v3/lend/purchase/update"), body).await
+    }
+
+    /// Lists purchase orders. `status` is either PENDING or DONE.
+    pub async fn get_lending_purchase_orders(
+        &self,
+        currency: &str,
+        status: &str,
+        purchase_order_no: Option<&str>,
+        current_page: Option<i32>,
+        page_size: Option<i32>,
+    ) -> Result<APIDatum<Pagination<LendingPurchaseOrder>>> {
+        let mut params = lending_order_params(currency, status, current_page, page_size);
+        if let Some(o) = purchase_order_no {
+            params.insert(String::from("purchaseOrderNo"), o.to_string());
+        }
+        self.get_margin_v3(String::from("/api/v3/purchase/orders"), params).await
+    }
+
+    /// Lists redeem orders. `status` is either PENDING or DONE.
+    pub async fn get_lending_redeem_orders(
+        &self,
+        currency: &str,
+        status: &str,
+        redeem_order_no: Option<&str>,
+        current_page: Option<i32>,
+        page_size: Option<i32>,
+    ) -> Result<APIDatum<Pagination<LendingRedeemOrder>>> {
+        let mut params = lending_order_params(currency, status, current_page, page_size);
+        if let Some(o) = redeem_order_no {
+            params.insert(String::from("redeemOrderNo"), o.to_string());
+        }
+        self.get_margin_v3(String::from("/api/v3/redeem/orders"), params).await
+    }
+
+    async fn post_margin_v3<T: DeserializeOwned>(&self, endpoint: String, body: Value) -> Result<T> {
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let body = body.to_string();
+        let headers = self.sign_headers_json(endpoint, &body, Method::POST)?;
+        let resp = self.post_json(url, Some(headers), body).await?.json().await?;
+        Ok(resp)
+    }
+
+    async fn get_margin_v3<T: DeserializeOwned>(&self, endpoint: String, params: HashMap<String, String>) -> Result<T> {
+        let query = format_query(&params);
+        let url = format!("{}{}{}", &self.prefix, endpoint, query);
+        let headers = self.sign_headers(endpoint, None, Some(query), Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+}
+
+fn lending_order_params(
+    currency: &str,
+    status: &str,
+    current_page: Option<i32>,
+    page_size: Option<i32>,
+) -> HashMap<String, String> {
+    let mut params: HashMap<String, String> = HashMap::new();
+    params.insert(String::from("currency"), currency.to_string());
+    params.insert(String::from("status"), status.to_string());
+    if let Some(c) = current_page {
+        params.insert(String::from("currentPage"), c.to_string());
+    }
+    if let Some(p) = page_size {
+        params.insert(String::from("pageSize"), p.to_string());
+    }
+    params
+}
+
+fn margin_limit_params(
+    client_oid: &str,
+    symbol: &str,
+    side: &str,
+    price: &str,
+    size: &str,
+    margin: Option<MarginOrderOptionals>,
+    optionals: Option<OrderOptionals<'_>>,
+) -> HashMap<String, String> {
+    let mut params = margin_order_params(client_oid, symbol, side, margin, optionals);
+    params.insert(String::from("type"), String::from("limit"));
+    params.insert(String::from("price"), price.to_string());
+    params.insert(String::from("size"), size.to_string());
+    params
+}
+
+fn margin_market_params(
+    client_oid: &str,
+    symbol: &str,
+    side: &str,
+    size: Option<&str>,
+    funds: Option<&str>,
+    margin: Option<MarginOrderOptionals>,
+    optionals: Option<OrderOptionals<'_>>,
+) -> HashMap<String, String> {
+    let mut params = margin_order_params(client_oid, symbol, side, margin, optionals);
+    params.insert(String::from("type"), String::from("market"));
+    if let Some(s) = size {
+        params.insert(String::from("size"), s.to_string());
+    }
+    if let Some(f) = funds {
+        params.insert(String::from("funds"), f.to_string());
+    }
+    params
+}
+
+fn isolated_borrow_params(
+    symbol: &str,
+    currency: &str,
+    size: &str,
+    borrow_strategy: &str,
+    max_rate: Option<&str>,
+    period: Option<&str>,
+) -> HashMap<String, String> {
+    let mut params: HashMap<String, String> = HashMap::new();
+    params.insert(String::from("symbol"), symbol.to_string());
+    params.insert(String::from("currency"), currency.to_string());
+    params.insert(String::from("size"), size.to_string());
+    params.insert(String::from("borrowStrategy"), borrow_strategy.to_string());
+    if let Some(m) = max_rate {
+        params.insert(String::from("maxRate"), m.to_string());
+    }
+    if let Some(p) = period {
+        params.insert(String::from("period"), p.to_string());
+    }
+    params
+}
+
+/// Body shared by the v3 borrow and repay endpoints.
+fn margin_loan_body(currency: &str, size: &str, isolated_symbol: Option<&str>, is_hf: bool) -> Value {
+    let mut body = json!({
+        "currency": currency,
+        "size": size,
+        "isIsolated": isolated_symbol.is_some(),
+        "isHf": is_hf,
+    });
+    if let Some(s) = isolated_symbol {
+        body["symbol"] = json!(s);
+    }
+    body
+}
+
+fn margin_order_params(
+    client_oid: &str,
+    symbol: &str,
+    side: &str,
+    margin: Option<MarginOrderOptionals>,
+    optionals: Option<OrderOptionals<'_>>,
+) -> HashMap<String, String> {
+    let mut params: HashMap<String, String> = HashMap::new();
+    if let Some(opt) = optionals {
+        params.extend(parse_order(opt));
+    }
+    let margin = margin.unwrap_or_default();
+    params.insert(String::from("marginModel"), margin.margin_model.unwrap_or_default().as_str().to_string());
+    if let Some(b) = margin.auto_borrow {
+        params.insert(String::from("autoBorrow"), b.to_string());
+    }
+    if let Some(r) = margin.auto_repay {
+        params.insert(String::from("autoRepay"), r.to_string());
+    }
+    params.insert(String::from("clientOid"), client_oid.to_string());
+    params.insert(String::from("symbol"), symbol.to_string());
+    params.insert(String::from("side"), side.to_string());
+    params
+}
+
+/// MarginOrderOptionals holds the margin specific inputs of margin orders.
+///
+/// Example:
+/// ``` rust
+/// use kucoin_api::margin::MarginOrderOptionals;
+/// use kucoin_api::model::margin::MarginModel;
+///
+///     let margin = MarginOrderOptionals::new()
+///         .margin_model(MarginModel::Isolated)
+///         .auto_borrow(true)
+///         .auto_repay(true)
+///         .build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MarginOrderOptionals {
+    pub margin_model: Option<MarginModel>,
+    pub auto_borrow: Option<bool>,
+    pub auto_repay: Option<bool>,
+}
+
+impl MarginOrderOptionals {
+    pub fn new() -> Self {
+        MarginOrderOptionals { margin_model: None, auto_borrow: None, auto_repay: None }
+    }
+
+    pub fn margin_model(&mut self, m: MarginModel) -> &mut Self {
+        self.margin_model = Some(m);
+        self
+    }
+
+    pub fn auto_borrow(&mut self, b: bool) -> &mut Self {
+        self.auto_borrow = Some(b);
+        self
+    }
+
+    pub fn auto_repay(&mut self, r: bool) -> &mut Self {
+        self.auto_repay = Some(r);
+        self
+    }
+
+    pub fn build(&self) -> Self {
+        *self
+    }
+}
+
+/// MarginHistoryOptionals contains a builder pattern for the filters of the v3 borrow, repay and
+/// interest history. `symbol` restricts the query to an isolated margin pair.
+///
+/// Example:
+/// ``` rust
+/// use kucoin_api::margin::MarginHistoryOptionals;
+///
+///     let options = MarginHistoryOptionals::new()
+///         .symbol("BTC-USDT")
+///         .page_size(50)
+///         .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MarginHistoryOptionals<'a> {
+    pub symbol: Option<&'a str>,
+    pub order_no: Option<&'a str>,
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+    pub current_page: Option<i32>,
+    pub page_size: Option<i32>,
+}
+
+impl<'a> MarginHistoryOptionals<'a> {
+    pub fn new() -> Self {
+        MarginHistoryOptionals {
+            symbol: None,
+            order_no: None,
+            start_time: None,
+            end_time: None,
+            current_page: None,
+            page_size: None,
+        }
+    }
+
+    pub fn symbol(&mut self, s: &'a str) -> &mut Self {
+        self.symbol = Some(s);
+        self
+    }
+
+    pub fn order_no(&mut self, s: &'a str) -> &mut Self {
+        self.order_no = Some(s);
+        self
+    }
+
+    pub fn start_time(&mut self, i: i64) -> &mut Self {
+        self.start_time = Some(i);
+        self
+    }
+
+    pub fn end_time(&mut self, i: i64) -> &mut Self {
+        self.end_time = Some(i);
+        self
+    }
+
+    pub fn current_page(&mut self, i: i32) -> &mut Self {
+        self.current_page = Some(i);
+        self
+    }
+
+    pub fn page_size(&mut self, i: i32) -> &mut Self {
+        self.page_size = Some(i);
+        self
+    }
+
+    pub fn build(&self) -> Self {
+        self.clone()
+    }
+
+    fn to_params(&self) -> HashMap<String, String> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("isIsolated"), self.symbol.is_some().to_string());
+        if let Some(s) = self.symbol {
+            params.insert(String::from("symbol"), s.to_string());
+        }
+        if let Some(o) = self.order_no {
+            params.insert(String::from("orderNo"), o.to_string());
+        }
+        if let Some(s) = self.start_time {
+            params.insert(String::from("startTime"), s.to_string());
+        }
+        if let Some(e) = self.end_time {
+            params.insert(String::from("endTime"), e.to_string());
+        }
+        if let Some(c) = self.current_page {
+            params.insert(String::from("currentPage"), c.to_string());
+        }
+        if let Some(p) = self.page_size {
+            params.insert(String::from("pageSize"), p.to_string());
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::margin::isolated_borrow_params;
+    use crate::margin::margin_limit_params;
+    use crate::margin::margin_loan_body;
+    use crate::margin::margin_market_params;
+    use crate::margin::MarginHistoryOptionals;
+    use crate::margin::MarginOrderOptionals;
+    use crate::model::margin::MarginModel;
+    use crate::trade::OrderOptionals;
+
+    #[test]
+    fn use_build_pattern_margin_order_optionals() {
+        let options = MarginOrderOptionals {
+            margin_model: Some(MarginModel::Isolated),
+            auto_borrow: Some(true),
+            auto_repay: None,
+        };
+        let build_options = MarginOrderOptionals::new().margin_model(MarginModel::Isolated).auto_borrow(true).build();
+        assert_eq!(options, build_options);
+    }
+
+    #[test]
+    fn use_build_pattern_margin_history_optionals() {
+        let options = MarginHistoryOptionals {
+            symbol: Some("BTC-USDT"),
+            order_no: None,
+            start_time: Some(1_686_627_780_000),
+            end_time: None,
+            current_page: None,
+            page_size: Some(50),
+        };
+        let build_options =
+            MarginHistoryOptionals::new().symbol("BTC-USDT").start_time(1_686_627_780_000).page_size(50).build();
+        assert_eq!(options, build_options);
+
+        let params = build_options.to_params();
+        assert_eq!(params["isIsolated"], "true");
+        assert_eq!(params["symbol"], "BTC-USDT");
+        assert_eq!(params["startTime"], "1686627780000");
+        assert!(!params.contains_key("orderNo"));
+        assert_eq!(MarginHistoryOptionals::new().build().to_params()["isIsolated"], "false");
+    }
+
+    #[test]
+    fn margin_order_params_include_margin_inputs() {
+        let margin = MarginOrderOptionals::new().auto_borrow(true).auto_repay(false).build();
+        let opts = OrderOptionals::new().stp("CN").post_only(true).build();
+        let params = margin_limit_params("oid", "BTC-USDT", "buy", "29000", "0.01", Some(margin), Some(opts));
+        assert_eq!(params["type"], "limit");
+        assert_eq!(params["price"], "29000");
+        assert_eq!(params["size"], "0.01");
+        assert_eq!(params["clientOid"], "oid");
+        assert_eq!(params["marginModel"], "cross");
+        assert_eq!(params["autoBorrow"], "true");
+        assert_eq!(params["autoRepay"], "false");
+        assert_eq!(params["stp"], "CN");
+        assert_eq!(params["postOnly"], "true");
+
+        let margin = MarginOrderOptionals::new().margin_model(MarginModel::Isolated).build();
+        let params = margin_market_params("oid", "BTC-USDT", "sell", None, Some("100"), Some(margin), None);
+        assert_eq!(params["type"], "market");
+        assert_eq!(params["funds"], "100");
+        assert_eq!(params["marginModel"], "isolated");
+        assert!(!params.contains_key("size"));
+        assert!(!params.contains_key("autoBorrow"));
+    }
+
+    #[test]
+    fn borrow_and_repay_params() {
+        let params = isolated_borrow_params("BTC-USDT", "USDT", "100", "FOK", None, Some("7,14"));
+        assert_eq!(params["borrowStrategy"], "FOK");
+        assert_eq!(params["period"], "7,14");
+        assert!(!params.contains_key("maxRate"));
+
+        let cross = margin_loan_body("USDT", "100", None, true);
+        assert_eq!(cross["isIsolated"], false);
+        assert_eq!(cross["isHf"], true);
+        assert!(cross.get("symbol").is_none());
+        let isolated = margin_loan_body("USDT", "100", Some("BTC-USDT"), false);
+        assert_eq!(isolated["isIsolated"], true);
+        assert_eq!(isolated["symbol"], "BTC-USDT");
+    }
 }
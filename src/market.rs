@@ -7,8 +7,8 @@ use crate::client::Kucoin;
 use crate::error::Result;
 use crate::model::market::AllTickers;
 use crate::model::market::Candle;
-use crate::model::market::Chain;
 use crate::model::market::Currency;
+use crate::model::market::CurrencyDetail;
 use crate::model::market::DailyStats;
 use crate::model::market::OrderBook;
 use crate::model::market::OrderBookType;
@@ -104,7 +104,23 @@ impl Kucoin {
         Ok(resp)
     }
 
-    pub async fn get_currency(&self, currency: &str, chain: Option<Chain>) -> Result<APIDatum<Currency>> {
+    /// `chain` is a chain id (e.g. `eth`) or name (e.g. `ERC20`), see
+    /// [`CurrencyRegistry`](crate::currency_registry::CurrencyRegistry) to resolve them. Callers
+    /// of the deprecated `Chain` enum pass `chain.as_str()`.
+    ///
+    /// Example:
+    /// ``` no_run
+    /// use kucoin_api::client::Kucoin;
+    /// use kucoin_api::client::KucoinEnv;
+    ///
+    /// # async fn run() -> kucoin_api::error::Result<()> {
+    ///     let api = Kucoin::new(KucoinEnv::Live, None)?;
+    ///     let btc = api.get_currency("BTC", None).await?;
+    ///     let usdt = api.get_currency("USDT", Some("ERC20")).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_currency(&self, currency: &str, chain: Option<&str>) -> Result<APIDatum<Currency>> {
         let mut endpoint = format!("/api/v1/currencies/{}", currency);
         if let Some(c) = chain {
             endpoint.push_str(&format!("?chain={c}"));
@@ -114,6 +130,26 @@ impl Kucoin {
         Ok(resp)
     }
 
+    /// Details of every listed currency with their chains.
+    pub async fn get_currency_details(&self) -> Result<APIData<CurrencyDetail>> {
+        let endpoint = String::from("/api/v3/currencies");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let resp = self.get(url, None).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Currency details including every chain it can be deposited and withdrawn on, or only
+    /// `chain` if given.
+    pub async fn get_currency_detail(&self, currency: &str, chain: Option<&str>) -> Result<APIDatum<CurrencyDetail>> {
+        let mut endpoint = format!("/api/v3/currencies/{}", currency);
+        if let Some(c) = chain {
+            endpoint.push_str(&format!("?chain={c}"));
+        }
+        let url = format!("{}{endpoint}", &self.prefix);
+        let resp = self.get(url, None).await?.json().await?;
+        Ok(resp)
+    }
+
     pub async fn get_fiat_prices(
         &self,
         base: Option<impl ToString>,
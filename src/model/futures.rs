@@ -0,0 +1,261 @@
+//! Futures endpoint response objects. Unlike the spot API, most futures amounts are JSON numbers.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum FuturesMarginMode {
+    Isolated,
+    Cross,
+}
+
+impl FuturesMarginMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FuturesMarginMode::Isolated => "ISOLATED",
+            FuturesMarginMode::Cross => "CROSS",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contract {
+    pub symbol: String,
+    pub root_symbol: String,
+    pub r#type: String,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub settle_currency: String,
+    pub max_order_qty: f64,
+    pub max_price: f64,
+    pub lot_size: f64,
+    pub tick_size: f64,
+    pub index_price_tick_size: f64,
+    pub multiplier: f64,
+    pub initial_margin: f64,
+    pub maintain_margin: f64,
+    pub max_risk_limit: f64,
+    pub min_risk_limit: f64,
+    pub risk_step: f64,
+    pub maker_fee_rate: f64,
+    pub taker_fee_rate: f64,
+    pub is_deleverage: bool,
+    pub is_quanto: bool,
+    pub is_inverse: bool,
+    pub mark_method: String,
+    pub funding_fee_rate: Option<f64>,
+    pub predicted_funding_fee_rate: Option<f64>,
+    pub open_interest: Option<String>,
+    pub mark_price: Option<f64>,
+    pub index_price: Option<f64>,
+    pub last_trade_price: Option<f64>,
+    pub next_funding_rate_time: Option<i64>,
+    pub max_leverage: f64,
+    pub status: String,
+}
+
+/// `ts` is in nanoseconds.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesTicker {
+    pub sequence: i64,
+    pub symbol: String,
+    pub side: String,
+    pub size: f64,
+    pub price: String,
+    pub best_bid_size: f64,
+    pub best_bid_price: String,
+    pub best_ask_size: f64,
+    pub best_ask_price: String,
+    pub trade_id: String,
+    pub ts: i64,
+}
+
+/// Level 2 snapshot, each level is `[price, size]`. `ts` is in nanoseconds.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesOrderBook {
+    pub symbol: String,
+    pub sequence: i64,
+    pub asks: Vec<(f64, f64)>,
+    pub bids: Vec<(f64, f64)>,
+    pub ts: i64,
+}
+
+/// Futures candle as returned by `/api/v1/kline/query`: `[time, open, high, low, close, volume]`,
+/// time in milliseconds.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FuturesCandle(pub i64, pub f64, pub f64, pub f64, pub f64, pub f64);
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub id: String,
+    pub symbol: String,
+    pub margin_mode: Option<FuturesMarginMode>,
+    pub auto_deposit: Option<bool>,
+    pub cross_mode: Option<bool>,
+    pub maint_margin_req: f64,
+    pub risk_limit: f64,
+    pub real_leverage: f64,
+    pub leverage: Option<f64>,
+    pub delev_percentage: f64,
+    pub opening_timestamp: i64,
+    pub current_timestamp: i64,
+    pub current_qty: i64,
+    pub current_cost: f64,
+    pub current_comm: f64,
+    pub unrealised_cost: f64,
+    pub realised_gross_cost: f64,
+    pub realised_cost: f64,
+    pub is_open: bool,
+    pub mark_price: f64,
+    pub mark_value: f64,
+    pub pos_cost: f64,
+    pub pos_init: f64,
+    pub pos_margin: f64,
+    pub realised_gross_pnl: f64,
+    pub realised_pnl: f64,
+    pub unrealised_pnl: f64,
+    pub unrealised_pnl_pcnt: f64,
+    pub unrealised_roe_pcnt: f64,
+    pub avg_entry_price: f64,
+    pub liquidation_price: f64,
+    pub bankrupt_price: f64,
+    pub settle_currency: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginModeResp {
+    pub symbol: String,
+    pub margin_mode: FuturesMarginMode,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossLeverage {
+    pub symbol: String,
+    pub leverage: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesOrderResp {
+    pub order_id: String,
+    pub client_oid: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesCancelResp {
+    pub cancelled_order_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesCancelByClientOidResp {
+    pub client_oid: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesOrder {
+    pub id: String,
+    pub symbol: String,
+    pub r#type: String,
+    pub side: String,
+    pub price: Option<String>,
+    pub size: i64,
+    pub value: String,
+    pub deal_value: String,
+    pub deal_size: i64,
+    pub stp: Option<String>,
+    pub stop: Option<String>,
+    pub stop_price_type: Option<String>,
+    pub stop_triggered: bool,
+    pub stop_price: Option<String>,
+    pub time_in_force: String,
+    pub post_only: bool,
+    pub hidden: bool,
+    pub iceberg: bool,
+    pub leverage: String,
+    pub force_hold: bool,
+    pub close_order: bool,
+    pub visible_size: Option<i64>,
+    pub client_oid: Option<String>,
+    pub remark: Option<String>,
+    pub tags: Option<String>,
+    pub is_active: bool,
+    pub cancel_exist: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub end_at: Option<i64>,
+    pub order_time: i64,
+    pub settle_currency: String,
+    pub margin_mode: Option<FuturesMarginMode>,
+    pub status: String,
+    pub filled_size: i64,
+    pub filled_value: String,
+    pub reduce_only: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesFill {
+    pub symbol: String,
+    pub trade_id: String,
+    pub order_id: String,
+    pub side: String,
+    pub liquidity: String,
+    pub force_taker: bool,
+    pub price: String,
+    pub size: i64,
+    pub value: String,
+    pub fee_rate: String,
+    pub fix_fee: String,
+    pub fee_currency: String,
+    pub stop: Option<String>,
+    pub fee: String,
+    pub order_type: String,
+    pub trade_type: String,
+    pub created_at: i64,
+    pub settle_currency: String,
+    pub trade_time: i64,
+    pub margin_mode: Option<FuturesMarginMode>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingHistory {
+    pub data_list: Vec<FundingRecord>,
+    pub has_more: bool,
+}
+
+/// A funding settlement of a position. `funding` is negative when it was paid.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRecord {
+    pub id: i64,
+    pub symbol: String,
+    pub time_point: i64,
+    pub funding_rate: f64,
+    pub mark_price: f64,
+    pub position_qty: i64,
+    pub position_cost: f64,
+    pub funding: f64,
+    pub settle_currency: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverview {
+    pub account_equity: f64,
+    #[serde(rename = "unrealisedPNL")]
+    pub unrealised_pnl: f64,
+    pub margin_balance: f64,
+    pub position_margin: f64,
+    pub order_margin: f64,
+    pub frozen_funds: f64,
+    pub available_balance: f64,
+    pub currency: String,
+}
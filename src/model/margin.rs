@@ -178,3 +178,171 @@ pub struct MarginTradeData {
     pub term: i32,
     pub timestamp: i64,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarginModel {
+    #[default]
+    Cross,
+    Isolated,
+}
+
+impl MarginModel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarginModel::Cross => "cross",
+            MarginModel::Isolated => "isolated",
+        }
+    }
+}
+
+/// `borrow_size` and `loan_apply_id` are only set when the order was placed with auto borrow.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginTradeOrderResp {
+    pub order_id: String,
+    pub borrow_size: Option<String>,
+    pub loan_apply_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsolatedAccounts {
+    pub total_conversion_balance: String,
+    pub liability_conversion_balance: String,
+    pub assets: Vec<IsolatedAccount>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsolatedAccount {
+    pub symbol: String,
+    pub status: String,
+    pub debt_ratio: String,
+    pub base_asset: IsolatedAsset,
+    pub quote_asset: IsolatedAsset,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsolatedAsset {
+    pub currency: String,
+    pub total_balance: String,
+    pub hold_balance: String,
+    pub available_balance: String,
+    pub liability: String,
+    pub interest: String,
+    pub borrowable_amount: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsolatedBorrowResp {
+    pub order_id: String,
+    pub currency: String,
+    pub actual_borrow_size: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginBorrowResp {
+    pub order_no: String,
+    pub actual_size: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginRepayResp {
+    pub timestamp: i64,
+    pub order_no: String,
+    pub actual_size: String,
+}
+
+/// v3 borrow order. `symbol` is only set for isolated margin.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginBorrowRecord {
+    pub order_no: String,
+    pub symbol: Option<String>,
+    pub currency: String,
+    pub size: String,
+    pub actual_size: String,
+    pub status: String,
+    pub created_time: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginRepayRecord {
+    pub order_no: String,
+    pub symbol: Option<String>,
+    pub currency: String,
+    pub size: String,
+    pub principal: String,
+    pub interest: String,
+    pub status: String,
+    pub created_time: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginInterestRecord {
+    pub currency: String,
+    pub day_ratio: String,
+    pub interest_amount: String,
+    pub created_time: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LendingCurrency {
+    pub currency: String,
+    pub purchase_enable: bool,
+    pub redeem_enable: bool,
+    pub increment: String,
+    pub min_purchase_size: String,
+    pub max_purchase_size: String,
+    pub min_interest_rate: String,
+    pub max_interest_rate: String,
+    pub interest_increment: String,
+    pub market_interest_rate: String,
+    pub auto_purchase_enable: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LendingMarketRate {
+    pub time: String,
+    pub market_interest_rate: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LendingOrderNo {
+    pub order_no: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LendingPurchaseOrder {
+    pub currency: String,
+    pub purchase_order_no: String,
+    pub purchase_size: String,
+    pub match_size: String,
+    pub interest_rate: String,
+    pub income_amount: String,
+    pub apply_time: i64,
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LendingRedeemOrder {
+    pub currency: String,
+    pub purchase_order_no: String,
+    pub redeem_order_no: String,
+    pub redeem_size: String,
+    pub receipt_size: String,
+    pub apply_time: Option<i64>,
+    pub status: String,
+}
@@ -141,6 +141,127 @@ pub struct Currency {
     is_debit_enabled: bool,
 }
 
+/// Currency details with the per chain deposit and withdrawal settings.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyDetail {
+    pub currency: String,
+    pub name: String,
+    pub full_name: String,
+    pub precision: u32,
+    pub confirms: Option<i32>,
+    pub contract_address: Option<String>,
+    pub is_margin_enabled: bool,
+    pub is_debit_enabled: bool,
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub chains: Vec<CurrencyChain>,
+}
+
+/// The currency list returns `null` chains for currencies that cannot be deposited or withdrawn.
+fn null_as_empty<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<CurrencyChain>, D::Error> {
+    let chains: Option<Vec<CurrencyChain>> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(chains.unwrap_or_default())
+}
+
+impl CurrencyDetail {
+    /// Looks a chain up by its id (e.g. `trx`) or name (e.g. `TRC20`), ignoring case.
+    pub fn chain(&self, chain: &str) -> Option<&CurrencyChain> {
+        self.chains.iter().find(|c| c.chain_id.eq_ignore_ascii_case(chain) || c.chain_name.eq_ignore_ascii_case(chain))
+    }
+
+    /// Resolves a chain id or name to the id of one of the currency's chains.
+    pub fn chain_id(&self, chain: &str) -> Option<ChainId> {
+        self.chain(chain).map(CurrencyChain::id)
+    }
+
+    pub fn chain_ids(&self) -> Vec<ChainId> {
+        self.chains.iter().map(CurrencyChain::id).collect()
+    }
+
+    /// Resolves the chain to use: the given chain id or name, or the currency's only chain when
+    /// none is given.
+    pub fn resolve_chain(&self, chain: Option<&str>) -> crate::error::Result<ChainId> {
+        match chain {
+            Some(c) => match self.chain_id(c) {
+                Some(id) => Ok(id),
+                None => Err(anyhow::anyhow!("{} is not available on chain {}", self.currency, c))?,
+            },
+            None => match self.chains.as_slice() {
+                [chain] => Ok(chain.id()),
+                chains => {
+                    let ids: Vec<&str> = chains.iter().map(|c| c.chain_id.as_str()).collect();
+                    Err(anyhow::anyhow!("{} requires a chain, one of: {}", self.currency, ids.join(", ")))?
+                },
+            },
+        }
+    }
+}
+
+/// `need_tag` is set for chains that require a memo on deposits and withdrawals.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyChain {
+    pub chain_name: String,
+    pub chain_id: String,
+    pub withdrawal_min_size: String,
+    pub withdrawal_min_fee: String,
+    /// Proportional withdrawal fee, charged when above `withdrawal_min_fee`
+    pub withdraw_fee_rate: Option<String>,
+    pub deposit_min_size: Option<String>,
+    pub withdraw_precision: Option<u32>,
+    pub max_withdraw: Option<String>,
+    pub max_deposit: Option<String>,
+    pub is_withdraw_enabled: bool,
+    pub is_deposit_enabled: bool,
+    pub confirms: Option<i32>,
+    pub pre_confirms: Option<i32>,
+    pub contract_address: Option<String>,
+    pub need_tag: bool,
+}
+
+impl CurrencyChain {
+    pub fn id(&self) -> ChainId {
+        ChainId::new(&self.chain_id)
+    }
+}
+
+/// Chain identifier as used by the v3 deposit and withdrawal endpoints (e.g. `trx`, `eth`,
+/// `bech32`). Obtained from a currency's chain list, see [`CurrencyDetail::chain_id`]. Always
+/// lower case, whatever the case the exchange returned.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(transparent)]
+pub struct ChainId(String);
+
+impl<'de> serde::Deserialize<'de> for ChainId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Ok(ChainId::new(&id))
+    }
+}
+
+impl ChainId {
+    pub(crate) fn new(id: &str) -> Self {
+        ChainId(id.to_ascii_lowercase())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for ChainId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for ChainId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[deprecated(note = "chains are resolved at runtime, see `CurrencyRegistry` and `ChainId`")]
 pub enum Chain {
     Native,
     Segwit,
@@ -149,6 +270,7 @@ pub enum Chain {
     TRC20,
 }
 
+#[allow(deprecated)]
 impl Chain {
     pub const VARIANTS: [&'static str; 5] = ["Native", "Segwit", "OMNI", "ERC20", "TRC20"];
 
@@ -163,6 +285,7 @@ impl Chain {
     }
 }
 
+#[allow(deprecated)]
 impl FromStr for Chain {
     type Err = Error;
 
@@ -178,6 +301,7 @@ impl FromStr for Chain {
     }
 }
 
+#[deprecated(note = "pass fiat currency codes as strings to `get_fiat_prices`")]
 pub enum Fiat {
     USD,
     EUR,
@@ -277,6 +401,7 @@ impl std::fmt::Display for Currency {
     }
 }
 
+#[allow(deprecated)]
 impl std::fmt::Display for Chain {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.as_str().fmt(f)
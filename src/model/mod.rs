@@ -1,11 +1,14 @@
 //! All Kucoin API endpoint response objects
 
 use serde::Serialize;
+pub mod futures;
 pub mod margin;
 pub mod market;
+pub mod oco;
 pub mod parameters;
 pub mod request;
 pub mod trade;
+pub mod trade_hf;
 pub mod user;
 pub mod websocket;
 
@@ -34,6 +37,17 @@ pub enum Method {
     DELETE,
 }
 
+impl Method {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::GET => "GET",
+            Method::POST => "POST",
+            Method::PUT => "PUT",
+            Method::DELETE => "DELETE",
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Pagination<T> {
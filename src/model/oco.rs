@@ -0,0 +1,56 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OcoStatus {
+    New,
+    Done,
+    Triggered,
+    Cancelled,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcoOrderResp {
+    pub order_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcoCancelResp {
+    pub cancelled_order_ids: Vec<String>,
+}
+
+/// An OCO order. `order_time` is in milliseconds.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcoOrder {
+    pub order_id: String,
+    pub symbol: String,
+    pub client_oid: String,
+    pub order_time: i64,
+    pub status: OcoStatus,
+}
+
+/// An OCO order together with its two linked orders, the limit (take profit) and the stop limit
+/// (stop loss) leg.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcoOrderDetails {
+    pub order_id: String,
+    pub symbol: String,
+    pub client_oid: String,
+    pub order_time: i64,
+    pub status: OcoStatus,
+    pub orders: Vec<OcoLinkedOrder>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcoLinkedOrder {
+    pub id: String,
+    pub symbol: String,
+    pub side: String,
+    pub price: String,
+    pub stop_price: String,
+    pub size: String,
+    pub status: OcoStatus,
+}
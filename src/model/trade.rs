@@ -49,9 +49,50 @@ pub struct OrderInfo {
     pub is_active: Option<bool>,
     pub cancel_exist: bool,
     pub created_at: i64,
+    /// Milliseconds, only reported by some endpoints
+    #[serde(default)]
+    pub last_updated_at: Option<i64>,
     pub trade_type: String,
 }
 
+/// Untriggered stop order as returned by the `/api/v1/stop-order` endpoints. `order_time` is in
+/// nanoseconds, `created_at` in milliseconds.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopOrderInfo {
+    pub id: String,
+    pub symbol: String,
+    pub user_id: Option<String>,
+    pub status: String,
+    pub r#type: String,
+    pub side: String,
+    pub price: Option<String>,
+    pub size: Option<String>,
+    pub funds: Option<String>,
+    pub stp: Option<String>,
+    pub time_in_force: String,
+    pub cancel_after: i64,
+    pub post_only: bool,
+    pub hidden: bool,
+    pub iceberg: bool,
+    pub visible_size: Option<String>,
+    pub channel: String,
+    pub client_oid: String,
+    pub remark: Option<String>,
+    pub tags: Option<String>,
+    pub order_time: i64,
+    pub domain_id: Option<String>,
+    pub trade_source: Option<String>,
+    pub trade_type: String,
+    pub fee_currency: Option<String>,
+    pub taker_fee_rate: Option<String>,
+    pub maker_fee_rate: Option<String>,
+    pub created_at: i64,
+    pub stop: String,
+    pub stop_trigger_time: Option<i64>,
+    pub stop_price: String,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct HistoricalOrder {
@@ -85,3 +126,40 @@ pub struct FillsInfo {
     pub created_at: i64,
     pub trade_type: String,
 }
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiOrderResp {
+    pub data: Vec<MultiOrderResult>,
+}
+
+/// Result for a single order of a batch placed with `post_multiple_orders`. `status` is either
+/// `success` or `fail`, in which case `fail_msg` holds the reason, or `unknown` when the request
+/// may have been processed but no response could be read: reconcile those orders by clientOid.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiOrderResult {
+    pub symbol: String,
+    pub side: String,
+    pub price: Option<String>,
+    pub size: Option<String>,
+    pub id: Option<String>,
+    pub client_oid: Option<String>,
+    pub status: String,
+    pub fail_msg: Option<String>,
+}
+
+impl MultiOrderResult {
+    pub const SUCCESS: &'static str = "success";
+    pub const FAIL: &'static str = "fail";
+    /// Set locally, never returned by the exchange
+    pub const UNKNOWN: &'static str = "unknown";
+
+    pub fn is_success(&self) -> bool {
+        self.status == MultiOrderResult::SUCCESS
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        self.status == MultiOrderResult::UNKNOWN
+    }
+}
@@ -0,0 +1,175 @@
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfOrderResp {
+    pub order_id: String,
+    pub client_oid: Option<String>,
+}
+
+/// Response of the synchronous HF endpoints, which wait for the matching engine before returning.
+/// `order_time` and `match_time` are in milliseconds.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfSyncOrderResp {
+    pub order_id: String,
+    pub client_oid: Option<String>,
+    pub order_time: i64,
+    pub origin_size: String,
+    pub deal_size: String,
+    pub remain_size: String,
+    pub canceled_size: String,
+    pub status: String,
+    pub match_time: i64,
+}
+
+/// Result for a single order of an HF batch. `unknown` is set locally, never by the exchange, when
+/// the request may have been processed but no response could be read: reconcile those orders by
+/// clientOid.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfMultiOrderResult {
+    pub order_id: Option<String>,
+    pub client_oid: Option<String>,
+    pub success: bool,
+    pub fail_msg: Option<String>,
+    #[serde(default)]
+    pub unknown: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfAlterResp {
+    pub new_order_id: String,
+    pub client_oid: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfCancelResp {
+    pub order_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfCancelByClientOidResp {
+    pub client_oid: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfSyncCancelResp {
+    pub order_id: Option<String>,
+    pub client_oid: Option<String>,
+    pub origin_size: String,
+    pub deal_size: String,
+    pub remain_size: String,
+    pub canceled_size: String,
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfCancelAllResp {
+    pub succeed_symbols: Vec<String>,
+    pub failed_symbols: Vec<HfCancelAllFailure>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfCancelAllFailure {
+    pub symbol: String,
+    pub error: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfActiveSymbols {
+    pub symbols: Vec<String>,
+}
+
+/// Page of HF history. Pass `last_id` back to fetch the next page.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfPage<T> {
+    pub last_id: i64,
+    pub items: Vec<T>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfOrderInfo {
+    pub id: String,
+    pub client_oid: String,
+    pub symbol: String,
+    pub op_type: String,
+    pub r#type: String,
+    pub side: String,
+    pub price: String,
+    pub size: String,
+    pub funds: String,
+    pub deal_size: String,
+    pub deal_funds: String,
+    pub fee: String,
+    pub fee_currency: String,
+    pub stp: Option<String>,
+    pub time_in_force: String,
+    pub post_only: bool,
+    pub hidden: bool,
+    pub iceberg: bool,
+    pub visible_size: String,
+    pub cancel_after: i64,
+    pub channel: String,
+    pub remark: Option<String>,
+    pub tags: Option<String>,
+    pub cancel_exist: bool,
+    pub created_at: i64,
+    pub last_updated_at: i64,
+    pub trade_type: String,
+    pub in_order_book: bool,
+    pub cancelled_size: String,
+    pub cancelled_funds: String,
+    pub remain_size: String,
+    pub remain_funds: String,
+    pub active: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HfFill {
+    pub id: i64,
+    pub order_id: String,
+    pub counter_order_id: String,
+    pub trade_id: i64,
+    pub symbol: String,
+    pub side: String,
+    pub liquidity: String,
+    pub r#type: String,
+    pub force_taker: bool,
+    pub price: String,
+    pub size: String,
+    pub funds: String,
+    pub fee: String,
+    pub fee_rate: String,
+    pub fee_currency: String,
+    pub stop: String,
+    pub trade_type: String,
+    pub created_at: i64,
+}
+
+/// `current_time` and `trigger_time` are in seconds.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelAllAfterResp {
+    pub current_time: i64,
+    pub trigger_time: i64,
+}
+
+/// Currently armed `cancel-all-after` countdown. `symbols` is a comma separated list, empty when
+/// every symbol is covered.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelAllAfterInfo {
+    pub timeout: i64,
+    pub symbols: String,
+    pub current_time: i64,
+    pub trigger_time: i64,
+}
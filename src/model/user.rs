@@ -1,3 +1,16 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::model::market::ChainId;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Invalid account type")]
+    InvalidAccountType,
+    #[error("Invalid API key permission")]
+    InvalidApiKeyPermission,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserInfo {
@@ -12,10 +25,123 @@ pub struct AccountId {
     pub id: String,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AccountType {
     Main,
     Trade,
+    TradeHf,
     Margin,
+    MarginV2,
+    Isolated,
+    IsolatedV2,
+    Pool,
+    Contract,
+}
+
+impl AccountType {
+    pub const VARIANTS: [&'static str; 9] =
+        ["main", "trade", "trade_hf", "margin", "margin_v2", "isolated", "isolated_v2", "pool", "contract"];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountType::Main => "main",
+            AccountType::Trade => "trade",
+            AccountType::TradeHf => "trade_hf",
+            AccountType::Margin => "margin",
+            AccountType::MarginV2 => "margin_v2",
+            AccountType::Isolated => "isolated",
+            AccountType::IsolatedV2 => "isolated_v2",
+            AccountType::Pool => "pool",
+            AccountType::Contract => "contract",
+        }
+    }
+
+    /// Upper case form used by the transferable balance and sub-account transfer endpoints.
+    pub fn as_upper_str(&self) -> &'static str {
+        match self {
+            AccountType::Main => "MAIN",
+            AccountType::Trade => "TRADE",
+            AccountType::TradeHf => "TRADE_HF",
+            AccountType::Margin => "MARGIN",
+            AccountType::MarginV2 => "MARGIN_V2",
+            AccountType::Isolated => "ISOLATED",
+            AccountType::IsolatedV2 => "ISOLATED_V2",
+            AccountType::Pool => "POOL",
+            AccountType::Contract => "CONTRACT",
+        }
+    }
+}
+
+impl FromStr for AccountType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "main" => Ok(AccountType::Main),
+            "trade" => Ok(AccountType::Trade),
+            "trade_hf" => Ok(AccountType::TradeHf),
+            "margin" => Ok(AccountType::Margin),
+            "margin_v2" => Ok(AccountType::MarginV2),
+            "isolated" => Ok(AccountType::Isolated),
+            "isolated_v2" => Ok(AccountType::IsolatedV2),
+            "pool" => Ok(AccountType::Pool),
+            "contract" => Ok(AccountType::Contract),
+            _ => Err(Error::InvalidAccountType),
+        }
+    }
+}
+
+impl Display for AccountType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// Account type as reported by the exchange: one of [`AccountType`], or the name of one this
+/// crate does not know yet, so that a newly added account type does not fail the whole response.
+/// Requests only take [`AccountType`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum AccountKind {
+    Known(AccountType),
+    Other(String),
+}
+
+impl AccountKind {
+    pub fn known(&self) -> Option<AccountType> {
+        match self {
+            AccountKind::Known(t) => Some(*t),
+            AccountKind::Other(_) => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            AccountKind::Known(t) => t.as_str(),
+            AccountKind::Other(s) => s,
+        }
+    }
+}
+
+impl From<AccountType> for AccountKind {
+    fn from(t: AccountType) -> Self {
+        AccountKind::Known(t)
+    }
+}
+
+impl PartialEq<AccountType> for AccountKind {
+    fn eq(&self, other: &AccountType) -> bool {
+        self.known() == Some(*other)
+    }
+}
+
+impl Display for AccountKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -23,7 +149,7 @@ pub enum AccountType {
 pub struct Accounts {
     pub id: String,
     pub currency: String,
-    pub r#type: String,
+    pub r#type: AccountKind,
     pub balance: String,
     pub available: String,
     pub holds: String,
@@ -108,14 +234,30 @@ pub struct DepositAddress {
     pub chain: String,
 }
 
+/// Deposit address from the v3 endpoints. `to` is the account credited on deposit, `main` or
+/// `trade`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositAddressV3 {
+    pub address: String,
+    pub memo: Option<String>,
+    pub chain_id: ChainId,
+    pub to: String,
+    pub expiration_date: Option<i64>,
+    pub currency: String,
+    pub contract_address: Option<String>,
+    pub chain_name: String,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DepositList {
     pub address: String,
     pub memo: String,
-    pub amount: i32,
-    pub fee: f32,
+    pub amount: String,
+    pub fee: String,
     pub currency: String,
+    pub chain: Option<String>,
     pub is_inner: bool,
     pub wallet_tx_id: String,
     pub status: String,
@@ -142,8 +284,9 @@ pub struct WithdrawalList {
     pub address: String,
     pub memo: String,
     pub currency: String,
-    pub amount: f32,
-    pub fee: f32,
+    pub chain: Option<String>,
+    pub amount: String,
+    pub fee: String,
     pub wallet_tx_id: String,
     pub is_inner: bool,
     pub status: String,
@@ -171,13 +314,15 @@ pub struct WithdrawalQuotas {
     pub currency: String,
     pub limit_BTC_amount: String,
     pub used_BTC_amount: String,
+    #[serde(default, alias = "limitQuotaCurrencyAmount")]
     pub limit_amount: String,
     pub remain_amount: String,
     pub available_amount: String,
+    #[serde(alias = "withdrawMinFee")]
     pub withdrawal_min_fee: String,
     pub inner_withdraw_min_fee: String,
     pub withdraw_min_size: String,
-    pub is_withdraw_enabled: String,
+    pub is_withdraw_enabled: bool,
     pub precision: i32,
     pub chain: String,
 }
@@ -185,5 +330,223 @@ pub struct WithdrawalQuotas {
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WithdrawalId {
-    withdrawal_id: String,
+    pub withdrawal_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseFee {
+    pub taker_fee_rate: String,
+    pub maker_fee_rate: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeFee {
+    pub symbol: String,
+    pub taker_fee_rate: String,
+    pub maker_fee_rate: String,
+}
+
+/// Trading access granted to a sub-account on creation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum SubAccountAccess {
+    Spot,
+    Futures,
+    Margin,
+}
+
+impl SubAccountAccess {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubAccountAccess::Spot => "Spot",
+            SubAccountAccess::Futures => "Futures",
+            SubAccountAccess::Margin => "Margin",
+        }
+    }
+}
+
+/// Permission of an API key. General (read only) is always granted. Earn and Transfer
+/// (withdrawals) cannot be granted to sub-account keys.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum ApiKeyPermission {
+    General,
+    Spot,
+    Margin,
+    Futures,
+    InnerTransfer,
+    Earn,
+    Transfer,
+}
+
+impl ApiKeyPermission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyPermission::General => "General",
+            ApiKeyPermission::Spot => "Spot",
+            ApiKeyPermission::Margin => "Margin",
+            ApiKeyPermission::Futures => "Futures",
+            ApiKeyPermission::InnerTransfer => "InnerTransfer",
+            ApiKeyPermission::Earn => "Earn",
+            ApiKeyPermission::Transfer => "Transfer",
+        }
+    }
+}
+
+impl FromStr for ApiKeyPermission {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "General" => Ok(ApiKeyPermission::General),
+            "Spot" => Ok(ApiKeyPermission::Spot),
+            "Margin" => Ok(ApiKeyPermission::Margin),
+            "Futures" => Ok(ApiKeyPermission::Futures),
+            "InnerTransfer" => Ok(ApiKeyPermission::InnerTransfer),
+            "Earn" => Ok(ApiKeyPermission::Earn),
+            "Transfer" => Ok(ApiKeyPermission::Transfer),
+            _ => Err(Error::InvalidApiKeyPermission),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountCreated {
+    pub uid: i64,
+    pub sub_name: String,
+    pub remarks: Option<String>,
+    pub access: String,
+}
+
+/// `permission` and `ip_whitelist` are comma separated lists.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubApiKey {
+    pub sub_name: String,
+    pub remark: String,
+    pub api_key: String,
+    pub permission: String,
+    pub ip_whitelist: Option<String>,
+    pub created_at: i64,
+}
+
+/// Returned once on creation, `api_secret` cannot be retrieved afterwards.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubApiKeyCreated {
+    pub sub_name: String,
+    pub remark: String,
+    pub api_key: String,
+    pub api_secret: String,
+    pub passphrase: String,
+    pub permission: String,
+    pub ip_whitelist: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubApiKeyUpdated {
+    pub sub_name: String,
+    pub api_key: String,
+    pub permission: String,
+    pub ip_whitelist: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubApiKeyDeleted {
+    pub sub_name: String,
+    pub api_key: String,
+}
+
+/// Direction of a universal transfer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TransferType {
+    /// Between account types of the same user
+    Internal,
+    ParentToSub,
+    SubToParent,
+}
+
+impl TransferType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferType::Internal => "INTERNAL",
+            TransferType::ParentToSub => "PARENT_TO_SUB",
+            TransferType::SubToParent => "SUB_TO_PARENT",
+        }
+    }
+}
+
+/// Ledger business types reported by get_transfer_history.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TransferHistoryType {
+    /// Transfers between account types
+    Transfer,
+    /// Transfers between master and sub-accounts
+    SubTransfer,
+}
+
+impl TransferHistoryType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferHistoryType::Transfer => "TRANSFER",
+            TransferHistoryType::SubTransfer => "SUB_TRANSFER",
+        }
+    }
+}
+
+/// Outcome of sweeping one sub-account, `order_id` is set on success and `fail_msg` otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepResult {
+    pub sub_user_id: String,
+    pub sub_name: String,
+    pub currency: String,
+    pub amount: String,
+    pub order_id: Option<String>,
+    pub fail_msg: Option<String>,
+}
+
+/// User level and sub-account usage and limits, in total and per sub-account type.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSummary {
+    pub level: i32,
+    pub sub_quantity: i32,
+    pub spot_sub_quantity: i32,
+    pub margin_sub_quantity: i32,
+    pub futures_sub_quantity: i32,
+    pub option_sub_quantity: Option<i32>,
+    pub max_sub_quantity: i32,
+    pub max_default_sub_quantity: i32,
+    pub max_spot_sub_quantity: i32,
+    pub max_margin_sub_quantity: i32,
+    pub max_futures_sub_quantity: i32,
+    pub max_option_sub_quantity: Option<i32>,
+}
+
+/// Information about the API key the client signs with. `permission` and `ip_whitelist` are comma
+/// separated lists, `sub_name` is only set for sub-account keys.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyInfo {
+    pub remark: String,
+    pub api_key: String,
+    pub api_version: i32,
+    pub permission: String,
+    pub ip_whitelist: Option<String>,
+    pub created_at: i64,
+    pub uid: i64,
+    pub is_master: bool,
+    pub sub_name: Option<String>,
+}
+
+impl ApiKeyInfo {
+    pub fn permissions(&self) -> Vec<&str> {
+        self.permission.split(',').map(str::trim).filter(|p| !p.is_empty()).collect()
+    }
 }
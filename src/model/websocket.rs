@@ -48,6 +48,17 @@ pub enum WSTopic {
     PositionChange,
     MarginTradeOrder(String),
     TradeOrders,
+    /// Futures topics, subscribe through a socket endpoint obtained from
+    /// [`KucoinFutures`](crate::futures_api::KucoinFutures).
+    FuturesTicker(String),
+    FuturesLevel2(String),
+    FuturesExecution(String),
+    FuturesInstrument(String),
+    FuturesPosition(String),
+    /// Order changes of a single contract, or of every contract when None
+    FuturesTradeOrders(Option<String>),
+    FuturesStopOrders,
+    FuturesWallet,
 }
 
 impl WSTopic {
@@ -72,6 +83,14 @@ impl WSTopic {
             WSTopic::PositionChange => "positionChange",
             WSTopic::MarginTradeOrder(_) => "marginTradeOrder",
             WSTopic::TradeOrders => "tradeOrders",
+            WSTopic::FuturesTicker(_) => "futuresTicker",
+            WSTopic::FuturesLevel2(_) => "futuresLevel2",
+            WSTopic::FuturesExecution(_) => "futuresExecution",
+            WSTopic::FuturesInstrument(_) => "futuresInstrument",
+            WSTopic::FuturesPosition(_) => "futuresPosition",
+            WSTopic::FuturesTradeOrders(_) => "futuresTradeOrders",
+            WSTopic::FuturesStopOrders => "futuresStopOrders",
+            WSTopic::FuturesWallet => "futuresWallet",
         }
     }
 }
@@ -100,6 +119,14 @@ impl FromStr for WSTopic {
             "positionChange" => Ok(WSTopic::PositionChange),
             "marginTradeOrder" => Ok(WSTopic::MarginTradeOrder("".to_string())),
             "tradeOrders" => Ok(WSTopic::TradeOrders),
+            "futuresTicker" => Ok(WSTopic::FuturesTicker("".to_string())),
+            "futuresLevel2" => Ok(WSTopic::FuturesLevel2("".to_string())),
+            "futuresExecution" => Ok(WSTopic::FuturesExecution("".to_string())),
+            "futuresInstrument" => Ok(WSTopic::FuturesInstrument("".to_string())),
+            "futuresPosition" => Ok(WSTopic::FuturesPosition("".to_string())),
+            "futuresTradeOrders" => Ok(WSTopic::FuturesTradeOrders(None)),
+            "futuresStopOrders" => Ok(WSTopic::FuturesStopOrders),
+            "futuresWallet" => Ok(WSTopic::FuturesWallet),
             _ => Err(Error::InvalidWSTopic),
         }
     }
@@ -150,6 +177,17 @@ pub enum KucoinWebsocketMsg {
     TradeFilledMsg(WSResp<TradeFilled>),
     TradeCanceledMsg(WSResp<TradeCanceled>),
     TradeUpdateMsg(WSResp<TradeUpdate>),
+    FuturesTickerMsg(WSResp<ContractTicker>),
+    FuturesLevel2Msg(WSResp<ContractLevel2>),
+    FuturesExecutionMsg(WSResp<ContractExecution>),
+    FuturesMarkIndexPriceMsg(WSResp<ContractMarkIndexPrice>),
+    FuturesFundingRateMsg(WSResp<ContractFundingRate>),
+    FuturesPositionChangeMsg(WSResp<ContractPositionChange>),
+    FuturesPositionSettlementMsg(WSResp<ContractPositionSettlement>),
+    FuturesOrderChangeMsg(WSResp<ContractOrderChange>),
+    FuturesStopOrderMsg(WSResp<ContractStopOrder>),
+    FuturesOrderMarginMsg(WSResp<ContractOrderMargin>),
+    FuturesAvailableBalanceMsg(WSResp<ContractAvailableBalance>),
     Error(String),
 }
 
@@ -603,6 +641,170 @@ pub struct TradeUpdate {
     pub ts: i64,
 }
 
+/// Futures ticker. Sizes are in lots and `ts` is in nanoseconds.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractTicker {
+    pub symbol: String,
+    pub sequence: i64,
+    pub best_bid_size: i64,
+    pub best_bid_price: String,
+    pub best_ask_price: String,
+    pub best_ask_size: i64,
+    pub ts: i64,
+}
+
+/// Futures level 2 update. `change` is formatted as `price,side,size`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractLevel2 {
+    pub sequence: i64,
+    pub change: String,
+    pub timestamp: i64,
+}
+
+impl ContractLevel2 {
+    /// Splits `change` into its price, side and size.
+    pub fn parse_change(&self) -> Option<(&str, &str, &str)> {
+        let mut parts = self.change.split(',');
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(price), Some(side), Some(size), None) => Some((price, side, size)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractExecution {
+    pub symbol: String,
+    pub sequence: i64,
+    pub side: String,
+    pub size: i64,
+    pub price: f64,
+    pub taker_order_id: String,
+    pub maker_order_id: String,
+    pub trade_id: String,
+    pub ts: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractMarkIndexPrice {
+    pub granularity: i64,
+    pub index_price: f64,
+    pub mark_price: f64,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractFundingRate {
+    pub granularity: i64,
+    pub funding_rate: f64,
+    pub timestamp: i64,
+}
+
+/// Position update. Mark price updates only carry the mark related fields, hence the options.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractPositionChange {
+    pub change_reason: Option<String>,
+    pub cross_mode: Option<bool>,
+    pub is_open: Option<bool>,
+    pub current_qty: Option<i64>,
+    pub avg_entry_price: Option<f64>,
+    pub liquidation_price: Option<f64>,
+    pub bankrupt_price: Option<f64>,
+    pub pos_margin: Option<f64>,
+    pub realised_pnl: Option<f64>,
+    pub mark_price: f64,
+    pub mark_value: f64,
+    pub maint_margin: Option<f64>,
+    pub real_leverage: f64,
+    pub unrealised_pnl: f64,
+    pub unrealised_pnl_pcnt: f64,
+    pub unrealised_roe_pcnt: f64,
+    pub delev_percentage: f64,
+    pub current_timestamp: i64,
+    pub settle_currency: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractPositionSettlement {
+    pub funding_time: i64,
+    pub qty: i64,
+    pub mark_price: f64,
+    pub funding_rate: f64,
+    pub funding_fee: f64,
+    pub ts: i64,
+    pub settle_currency: String,
+}
+
+/// Futures order change. `type` is one of open, match, filled, canceled or update.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractOrderChange {
+    pub order_id: String,
+    pub symbol: String,
+    pub r#type: String,
+    pub status: String,
+    pub order_type: Option<String>,
+    pub side: String,
+    pub price: Option<String>,
+    pub size: String,
+    pub remain_size: String,
+    pub filled_size: String,
+    pub canceled_size: Option<String>,
+    pub match_size: Option<String>,
+    pub match_price: Option<String>,
+    pub trade_id: Option<String>,
+    pub client_oid: Option<String>,
+    pub old_size: Option<String>,
+    pub liquidity: Option<String>,
+    pub order_time: i64,
+    pub ts: i64,
+}
+
+/// Futures stop order event. `type` is one of open, triggered or cancel, `error` is set when the
+/// order failed to be placed after triggering.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractStopOrder {
+    pub order_id: String,
+    pub symbol: String,
+    pub r#type: String,
+    pub order_type: String,
+    pub side: String,
+    pub size: String,
+    pub order_price: Option<String>,
+    pub stop: String,
+    pub stop_price: String,
+    pub stop_price_type: String,
+    pub trigger_success: Option<bool>,
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub ts: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractOrderMargin {
+    pub order_margin: f64,
+    pub currency: String,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractAvailableBalance {
+    pub available_balance: f64,
+    pub hold_balance: f64,
+    pub currency: String,
+    pub timestamp: i64,
+}
+
 impl Display for KucoinWebsocketMsg {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         serde_json::to_string(self).unwrap().fmt(f)
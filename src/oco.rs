@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use reqwest::header;
+
+use super::client::Kucoin;
+use super::model::oco::OcoCancelResp;
+use super::model::oco::OcoOrder;
+use super::model::oco::OcoOrderDetails;
+use super::model::oco::OcoOrderResp;
+use super::model::APIDatum;
+use super::model::Method;
+use super::model::Pagination;
+use super::utils::format_query;
+use crate::error::Result;
+
+impl Kucoin {
+    /// Places an OCO order: a limit order at `price` linked to a stop limit order triggered at
+    /// `stop_price` and placed at `limit_price`. When either one executes the other is canceled.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn post_oco_order(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        price: &str,
+        size: &str,
+        stop_price: &str,
+        limit_price: &str,
+        remark: Option<&str>,
+    ) -> Result<APIDatum<OcoOrderResp>> {
+        let endpoint = String::from("/api/v3/oco/order");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("clientOid"), client_oid.to_string());
+        params.insert(String::from("symbol"), symbol.to_string());
+        params.insert(String::from("side"), side.to_string());
+        params.insert(String::from("price"), price.to_string());
+        params.insert(String::from("size"), size.to_string());
+        params.insert(String::from("stopPrice"), stop_price.to_string());
+        params.insert(String::from("limitPrice"), limit_price.to_string());
+        params.insert(String::from("tradeType"), String::from("TRADE"));
+        if let Some(r) = remark {
+            params.insert(String::from("remark"), r.to_string());
+        };
+        self.ensure_live(&endpoint, &params)?;
+        let headers: header::HeaderMap = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Cancels an OCO order and both of its linked orders.
+    pub async fn cancel_oco_order(&self, order_id: &str) -> Result<APIDatum<OcoCancelResp>> {
+        let endpoint = format!("/api/v3/oco/order/{}", order_id);
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers: header::HeaderMap = self.sign_headers(endpoint, None, None, Method::DELETE)?;
+        let resp = self.delete(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    pub async fn cancel_oco_order_by_client_oid(&self, client_oid: &str) -> Result<APIDatum<OcoCancelResp>> {
+        let endpoint = format!("/api/v3/oco/client-order/{}", client_oid);
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers: header::HeaderMap = self.sign_headers(endpoint, None, None, Method::DELETE)?;
+        let resp = self.delete(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Cancels OCO orders in bulk, optionally limited to a symbol and/or a list of order ids.
+    /// Without any filter every open OCO order is canceled.
+    pub async fn cancel_oco_orders(
+        &self,
+        symbol: Option<&str>,
+        order_ids: Option<&[&str]>,
+    ) -> Result<APIDatum<OcoCancelResp>> {
+        let endpoint = String::from("/api/v3/oco/orders");
+        let url: String;
+        let headers: header::HeaderMap;
+        let mut params: HashMap<String, String> = HashMap::new();
+        if let Some(s) = symbol {
+            params.insert(String::from("symbol"), s.to_string());
+        };
+        if let Some(ids) = order_ids {
+            params.insert(String::from("orderIds"), ids.join(","));
+        };
+        if !params.is_empty() {
+            let query = format_query(&params);
+            url = format!("{}{}{}", &self.prefix, endpoint, query);
+            headers = self.sign_headers(endpoint, None, Some(query), Method::DELETE)?;
+        } else {
+            url = format!("{}{}", &self.prefix, endpoint);
+            headers = self.sign_headers(endpoint, None, None, Method::DELETE)?;
+        };
+        let resp = self.delete(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    pub async fn get_oco_order(&self, order_id: &str) -> Result<APIDatum<OcoOrder>> {
+        let endpoint = format!("/api/v3/oco/order/{}", order_id);
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers: header::HeaderMap = self.sign_headers(endpoint, None, None, Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    pub async fn get_oco_order_by_client_oid(&self, client_oid: &str) -> Result<APIDatum<OcoOrder>> {
+        let endpoint = format!("/api/v3/oco/client-order/{}", client_oid);
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers: header::HeaderMap = self.sign_headers(endpoint, None, None, Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Returns an OCO order together with the state of its linked orders.
+    pub async fn get_oco_order_details(&self, order_id: &str) -> Result<APIDatum<OcoOrderDetails>> {
+        let endpoint = format!("/api/v3/oco/order/details/{}", order_id);
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers: header::HeaderMap = self.sign_headers(endpoint, None, None, Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    pub async fn get_oco_orders(
+        &self,
+        optionals: Option<OcoOrderOptionals<'_>>,
+    ) -> Result<APIDatum<Pagination<OcoOrder>>> {
+        let endpoint = String::from("/api/v3/oco/orders");
+        let url: String;
+        let headers: header::HeaderMap;
+        let mut params: HashMap<String, String> = HashMap::new();
+        if let Some(opts) = optionals {
+            if let Some(o) = opts.symbol {
+                params.insert("symbol".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.start_at {
+                params.insert("startAt".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.end_at {
+                params.insert("endAt".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.order_ids {
+                params.insert("orderIds".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.current_page {
+                params.insert("currentPage".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.page_size {
+                params.insert("pageSize".to_string(), o.to_string());
+            };
+        };
+        if !params.is_empty() {
+            let query = format_query(&params);
+            url = format!("{}{}{}", &self.prefix, endpoint, query);
+            headers = self.sign_headers(endpoint, None, Some(query), Method::GET)?;
+        } else {
+            url = format!("{}{}", &self.prefix, endpoint);
+            headers = self.sign_headers(endpoint, None, None, Method::GET)?;
+        };
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+}
+
+/// OcoOrderOptionals contains a builder pattern for the filters of get_oco_orders.
+///
+/// Example:
+/// ``` rust
+/// use kucoin_api::oco::OcoOrderOptionals;
+///
+///     let options = OcoOrderOptionals::new()
+///         .symbol("BTC-USDT")
+///         .page_size(20)
+///         .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OcoOrderOptionals<'a> {
+    pub symbol: Option<&'a str>,
+    pub start_at: Option<i64>,
+    pub end_at: Option<i64>,
+    /// Comma separated list of order ids
+    pub order_ids: Option<&'a str>,
+    pub current_page: Option<i32>,
+    pub page_size: Option<i32>,
+}
+
+impl<'a> OcoOrderOptionals<'a> {
+    pub fn new() -> Self {
+        OcoOrderOptionals {
+            symbol: None,
+            start_at: None,
+            end_at: None,
+            order_ids: None,
+            current_page: None,
+            page_size: None,
+        }
+    }
+
+    pub fn symbol(&mut self, s: &'a str) -> &mut Self {
+        self.symbol = Some(s);
+        self
+    }
+
+    pub fn start_at(&mut self, i: i64) -> &mut Self {
+        self.start_at = Some(i);
+        self
+    }
+
+    pub fn end_at(&mut self, i: i64) -> &mut Self {
+        self.end_at = Some(i);
+        self
+    }
+
+    pub fn order_ids(&mut self, s: &'a str) -> &mut Self {
+        self.order_ids = Some(s);
+        self
+    }
+
+    pub fn current_page(&mut self, i: i32) -> &mut Self {
+        self.current_page = Some(i);
+        self
+    }
+
+    pub fn page_size(&mut self, i: i32) -> &mut Self {
+        self.page_size = Some(i);
+        self
+    }
+
+    pub fn build(&self) -> Self {
+        OcoOrderOptionals {
+            symbol: self.symbol,
+            start_at: self.start_at,
+            end_at: self.end_at,
+            order_ids: self.order_ids,
+            current_page: self.current_page,
+            page_size: self.page_size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::model::oco::OcoOrderDetails;
+    use crate::model::oco::OcoStatus;
+    use crate::oco::OcoOrderOptionals;
+
+    #[test]
+    fn use_build_pattern_oco_order_optionals() {
+        let options = OcoOrderOptionals {
+            symbol: Some("BTC-USDT"),
+            start_at: Some(1_580_683_419_725),
+            end_at: None,
+            order_ids: None,
+            current_page: Some(1),
+            page_size: Some(50),
+        };
+
+        let build_options = OcoOrderOptionals::new()
+            .symbol("BTC-USDT")
+            .start_at(1_580_683_419_725)
+            .current_page(1)
+            .page_size(50)
+            .build();
+
+        assert_eq!(options, build_options)
+    }
+
+    #[test]
+    fn deserialize_oco_order_details() {
+        let json = r#"{
+            "orderId": "6572fdd65723280007deb5e0",
+            "symbol": "FRM-USDT",
+            "clientOid": "9a05f68d4a9b4b2a8d7a5bdbb6f5d1e0",
+            "orderTime": 1702034902724,
+            "status": "NEW",
+            "orders": [
+                {"id": "vs8hoo8ksc8mario0035a74n", "symbol": "FRM-USDT", "side": "buy", "price": "1.00000000000000000000", "stopPrice": "1.00000000000000000000", "size": "10.00000000000000000000", "status": "NEW"},
+                {"id": "vs8hoo8ksc8mario0035a74o", "symbol": "FRM-USDT", "side": "buy", "price": "3.00000000000000000000", "stopPrice": "0.06000000000000000000", "size": "10.00000000000000000000", "status": "NEW"}
+            ]
+        }"#;
+        let details: OcoOrderDetails = serde_json::from_str(json).unwrap();
+        assert_eq!(details.status, OcoStatus::New);
+        assert_eq!(details.orders.len(), 2);
+        assert_eq!(details.orders[1].stop_price, "0.06000000000000000000");
+    }
+}
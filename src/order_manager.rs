@@ -0,0 +1,472 @@
+//! Local order state tracking
+//!
+//! [`OrderManager`] keeps the state of every order it knows about, keyed by `clientOid`. Orders are
+//! fed from REST placement results, the private `/spotMarket/tradeOrders` websocket channel
+//! ([`WSTopic::TradeOrders`](crate::model::websocket::WSTopic::TradeOrders)) and periodic
+//! reconciliation against `get_orders`/`get_order`, e.g. after a websocket reconnect.
+//!
+//! Every state change is published both on a per-order watch channel and on a broadcast channel of
+//! [`OrderTransition`]s.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use anyhow::anyhow;
+use futures::prelude::*;
+use rust_decimal::Decimal;
+use tokio::sync::broadcast;
+use tokio::sync::watch;
+
+use crate::client::Kucoin;
+use crate::error::Result;
+use crate::model::trade::OrderInfo;
+use crate::model::websocket::KucoinWebsocketMsg;
+use crate::trade::OrderInfoOptionals;
+use crate::trade::OrderOptionals;
+
+const TRANSITION_CAPACITY: usize = 1024;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderState {
+    /// Tracked locally, not yet acknowledged by Kucoin
+    New,
+    Open,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Rejected,
+}
+
+impl OrderState {
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, OrderState::Filled | OrderState::Canceled | OrderState::Rejected)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedOrder {
+    pub client_oid: String,
+    pub order_id: Option<String>,
+    pub symbol: String,
+    pub side: String,
+    pub price: Option<String>,
+    pub size: Option<String>,
+    pub filled_size: String,
+    pub remain_size: Option<String>,
+    pub state: OrderState,
+    /// Rejection reason or Kucoin error message, if any
+    pub reason: Option<String>,
+    /// Time of the last applied update as reported by Kucoin, in nanoseconds
+    pub ts: i64,
+}
+
+impl TrackedOrder {
+    pub fn new(client_oid: &str, symbol: &str, side: &str) -> Self {
+        TrackedOrder {
+            client_oid: client_oid.to_string(),
+            order_id: None,
+            symbol: symbol.to_string(),
+            side: side.to_string(),
+            price: None,
+            size: None,
+            filled_size: String::from("0"),
+            remain_size: None,
+            state: OrderState::New,
+            reason: None,
+            ts: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderTransition {
+    pub from: OrderState,
+    pub to: OrderState,
+    pub order: TrackedOrder,
+}
+
+/// Tracks orders by `clientOid`. Cloning the manager yields another handle to the same state.
+///
+/// Example:
+/// ``` ignore
+/// use kucoin_api::order_manager::OrderManager;
+///
+///     let orders = OrderManager::new();
+///     orders.post_limit_order(&api, "my-oid-1", "BTC-USDT", "buy", "20000", "0.001", None).await?;
+///
+///     // Feed the private tradeOrders channel
+///     while let Some(msg) = ws.try_next().await? {
+///         orders.apply(&msg);
+///     }
+///
+///     // Elsewhere
+///     let filled = orders.wait_until_done("my-oid-1").await?;
+/// ```
+#[derive(Clone)]
+pub struct OrderManager {
+    orders: Arc<Mutex<HashMap<String, watch::Sender<TrackedOrder>>>>,
+    transitions: broadcast::Sender<OrderTransition>,
+}
+
+impl Default for OrderManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderManager {
+    pub fn new() -> Self {
+        let (transitions, _) = broadcast::channel(TRANSITION_CAPACITY);
+        OrderManager { orders: Arc::new(Mutex::new(HashMap::new())), transitions }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, watch::Sender<TrackedOrder>>> {
+        self.orders.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Starts tracking an order before it is sent. Replaces any existing order with the same
+    /// `clientOid`. A transition is only published when the order is tracked in a state other
+    /// than `New`.
+    pub fn track(&self, order: TrackedOrder) {
+        let (tx, _) = watch::channel(order.clone());
+        self.lock().insert(order.client_oid.clone(), tx);
+        if order.state != OrderState::New {
+            let _ = self.transitions.send(OrderTransition { from: OrderState::New, to: order.state, order });
+        }
+    }
+
+    pub fn get(&self, client_oid: &str) -> Option<TrackedOrder> {
+        self.lock().get(client_oid).map(|tx| tx.borrow().clone())
+    }
+
+    pub fn orders(&self) -> Vec<TrackedOrder> {
+        self.lock().values().map(|tx| tx.borrow().clone()).collect()
+    }
+
+    pub fn open_orders(&self) -> Vec<TrackedOrder> {
+        self.orders().into_iter().filter(|o| !o.state.is_terminal()).collect()
+    }
+
+    /// Drops every order in a terminal state, returning how many were removed.
+    pub fn prune(&self) -> usize {
+        let mut orders = self.lock();
+        let before = orders.len();
+        orders.retain(|_, tx| !tx.borrow().state.is_terminal());
+        before - orders.len()
+    }
+
+    /// Subscribes to all state transitions.
+    pub fn transitions(&self) -> broadcast::Receiver<OrderTransition> {
+        self.transitions.subscribe()
+    }
+
+    /// Subscribes to the latest state of a single order.
+    pub fn watch(&self, client_oid: &str) -> Option<watch::Receiver<TrackedOrder>> {
+        self.lock().get(client_oid).map(|tx| tx.subscribe())
+    }
+
+    /// Stream of snapshots of a single order, starting with its current state and ending after it
+    /// reaches a terminal state.
+    pub fn updates(&self, client_oid: &str) -> Option<impl Stream<Item = TrackedOrder>> {
+        let rx = self.watch(client_oid)?;
+        Some(stream::unfold((rx, true, false), |(mut rx, first, done)| async move {
+            if done {
+                return None;
+            }
+            if !first && rx.changed().await.is_err() {
+                return None;
+            }
+            let order = rx.borrow().clone();
+            let done = order.state.is_terminal();
+            Some((order, (rx, false, done)))
+        }))
+    }
+
+    /// Resolves once the order reaches a terminal state.
+    pub async fn wait_until_done(&self, client_oid: &str) -> Result<TrackedOrder> {
+        let mut rx = self.watch(client_oid).ok_or_else(|| anyhow!("Unknown clientOid: {}", client_oid))?;
+        loop {
+            let order = rx.borrow().clone();
+            if order.state.is_terminal() {
+                return Ok(order);
+            }
+            if rx.changed().await.is_err() {
+                Err(anyhow!("Order {} is no longer tracked", client_oid))?
+            }
+        }
+    }
+
+    /// Applies `f` to the tracked order and publishes the transition. Unknown orders are created
+    /// from `create` (symbol and side), or left alone when it is None. Returns whether the order
+    /// is tracked.
+    fn modify<F>(&self, client_oid: &str, create: Option<(&str, &str)>, f: F) -> bool
+    where
+        F: FnOnce(&mut TrackedOrder),
+    {
+        let mut orders = self.lock();
+        if !orders.contains_key(client_oid) {
+            match create {
+                Some((symbol, side)) => {
+                    let (tx, _) = watch::channel(TrackedOrder::new(client_oid, symbol, side));
+                    orders.insert(client_oid.to_string(), tx);
+                },
+                None => return false,
+            }
+        }
+        let tx = &orders[client_oid];
+        let mut order = tx.borrow().clone();
+        let from = order.state;
+        if from.is_terminal() {
+            return true;
+        }
+        f(&mut order);
+        if *tx.borrow() == order {
+            return true;
+        }
+        tx.send_replace(order.clone());
+        drop(orders);
+        if from != order.state {
+            let _ = self.transitions.send(OrderTransition { from, to: order.state, order });
+        }
+        true
+    }
+
+    /// Records the order id returned when the order was accepted by the REST endpoint. Fails if
+    /// the order is not tracked.
+    pub fn mark_accepted(&self, client_oid: &str, order_id: &str) -> Result<()> {
+        if !self.modify(client_oid, None, |o| o.order_id = Some(order_id.to_string())) {
+            Err(anyhow!("Unknown clientOid: {}", client_oid))?
+        }
+        Ok(())
+    }
+
+    /// Marks an order as rejected, e.g. when the REST placement call failed. Fails if the order is
+    /// not tracked.
+    pub fn mark_rejected(&self, client_oid: &str, reason: &str) -> Result<()> {
+        let tracked = self.modify(client_oid, None, |o| {
+            o.state = OrderState::Rejected;
+            o.reason = Some(reason.to_string());
+        });
+        if !tracked {
+            Err(anyhow!("Unknown clientOid: {}", client_oid))?
+        }
+        Ok(())
+    }
+
+    /// Applies a message from the private `tradeOrders` channel. Other messages are ignored.
+    pub fn apply(&self, msg: &KucoinWebsocketMsg) {
+        macro_rules! apply_event {
+            ($d:expr, $state:expr) => {{
+                let state: Option<OrderState> = $state;
+                let d = $d;
+                let key = if d.client_oid.is_empty() { &d.order_id } else { &d.client_oid };
+                self.modify(key, Some((&d.symbol, &d.side)), |o| {
+                    o.order_id = Some(d.order_id.clone());
+                    if !d.price.is_empty() {
+                        o.price = Some(d.price.clone());
+                    }
+                    o.size = Some(d.size.clone());
+                    o.filled_size = d.filled_size.clone();
+                    o.remain_size = Some(d.remain_size.clone());
+                    o.ts = d.ts;
+                    o.state = state.unwrap_or_else(|| partial_or_open(&d.filled_size));
+                });
+            }};
+        }
+        match msg {
+            KucoinWebsocketMsg::TradeOpenMsg(m) => apply_event!(&m.data, None),
+            KucoinWebsocketMsg::TradeMatchMsg(m) => apply_event!(&m.data, None),
+            KucoinWebsocketMsg::TradeUpdateMsg(m) => apply_event!(&m.data, None),
+            KucoinWebsocketMsg::TradeFilledMsg(m) => apply_event!(&m.data, Some(OrderState::Filled)),
+            KucoinWebsocketMsg::TradeCanceledMsg(m) => apply_event!(&m.data, Some(OrderState::Canceled)),
+            _ => (),
+        }
+    }
+
+    /// Applies a REST order snapshot.
+    pub fn apply_order_info(&self, info: &OrderInfo) {
+        let key = if info.client_oid.is_empty() { &info.id } else { &info.client_oid };
+        self.modify(key, Some((&info.symbol, &info.side)), |o| {
+            o.order_id = Some(info.id.clone());
+            o.price = Some(info.price.clone());
+            o.size = Some(info.size.clone());
+            o.filled_size = info.deal_size.clone();
+            // Snapshots only carry their update time on some endpoints, and never move ts back.
+            let updated_at = info.last_updated_at.unwrap_or(info.created_at);
+            o.ts = o.ts.max(updated_at.saturating_mul(1_000_000));
+            o.state = if info.is_active.unwrap_or(false) {
+                partial_or_open(&info.deal_size)
+            } else if info.cancel_exist {
+                OrderState::Canceled
+            } else {
+                OrderState::Filled
+            };
+        });
+    }
+
+    /// Places a limit order and tracks it, recording the order id or the rejection reason.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn post_limit_order(
+        &self,
+        api: &Kucoin,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        price: &str,
+        size: &str,
+        optionals: Option<OrderOptionals<'_>>,
+    ) -> Result<TrackedOrder> {
+        let mut order = TrackedOrder::new(client_oid, symbol, side);
+        order.price = Some(price.to_string());
+        order.size = Some(size.to_string());
+        self.track(order);
+        match api.post_limit_order(client_oid, symbol, side, price, size, optionals).await {
+            Ok(resp) => match resp.data {
+                Some(d) => self.mark_accepted(client_oid, &d.order_id)?,
+                None => self.mark_rejected(client_oid, &resp.msg.unwrap_or(resp.code))?,
+            },
+            Err(e) => {
+                self.mark_rejected(client_oid, &e.to_string())?;
+                return Err(e);
+            },
+        }
+        self.get(client_oid).ok_or_else(|| anyhow!("Order {} is no longer tracked", client_oid).into())
+    }
+
+    /// Reconciles every non terminal order with Kucoin. Active orders are fetched in bulk, orders
+    /// missing from the active list are queried individually to learn their final state. Intended
+    /// to be called after (re)connecting the private websocket.
+    pub async fn reconcile(&self, api: &Kucoin) -> Result<()> {
+        let mut active = HashSet::new();
+        let mut page = 1;
+        loop {
+            let opts = OrderInfoOptionals::new().status("active").current_page(page).page_size(500).build();
+            let resp = api.get_orders(Some(opts)).await?;
+            let data = match resp.data {
+                Some(d) => d,
+                None => Err(anyhow!("Failed to list active orders: {:?}", resp.msg))?,
+            };
+            for info in data.items.iter() {
+                self.apply_order_info(info);
+                active.insert(info.id.clone());
+            }
+            if page >= data.total_page {
+                break;
+            }
+            page += 1;
+        }
+        for order_id in self.missing_from(&active) {
+            let resp = api.get_order(&order_id).await?;
+            if let Some(info) = resp.data {
+                self.apply_order_info(&info);
+            }
+        }
+        Ok(())
+    }
+
+    /// Ids of the non terminal orders that are not in `active`, i.e. whose final state is unknown.
+    fn missing_from(&self, active: &HashSet<String>) -> Vec<String> {
+        self.open_orders().into_iter().filter_map(|o| o.order_id).filter(|id| !active.contains(id)).collect()
+    }
+}
+
+fn partial_or_open(filled_size: &str) -> OrderState {
+    match filled_size.parse::<Decimal>() {
+        Ok(f) if f > Decimal::ZERO => OrderState::PartiallyFilled,
+        _ => OrderState::Open,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use tokio_tungstenite::tungstenite::Message;
+
+    use crate::model::trade::OrderInfo;
+    use crate::model::APIDatum;
+    use crate::order_manager::partial_or_open;
+    use crate::order_manager::OrderManager;
+    use crate::order_manager::OrderState;
+    use crate::order_manager::TrackedOrder;
+    use crate::websocket::parse_message;
+
+    /// `/spotMarket/tradeOrders` messages of a buy order filled in two matches, then a late cancel.
+    const TRADE_ORDERS: [&str; 5] = [
+        r#"{"type":"message","topic":"/spotMarket/tradeOrders","subject":"orderChange","channelType":"private","data":{"symbol":"BTC-USDT","orderType":"limit","side":"buy","orderId":"6487e5c4e2c3b40001a4f28e","type":"open","orderTime":1686627780125000000,"size":"1","filledSize":"0","price":"26000","clientOid":"c-1","remainSize":"1","status":"open","ts":1686627780125000000}}"#,
+        r#"{"type":"message","topic":"/spotMarket/tradeOrders","subject":"orderChange","channelType":"private","data":{"symbol":"BTC-USDT","orderType":"limit","side":"buy","liquidity":"maker","orderId":"6487e5c4e2c3b40001a4f28e","type":"match","orderTime":1686627780125000000,"size":"1","filledSize":"0.5","price":"26000","matchPrice":"26000","matchSize":"0.5","tradeId":"6487e5f22d9e7d0001a4d3c1","clientOid":"c-1","remainSize":"0.5","status":"match","ts":1686627826184000000}}"#,
+        r#"{"type":"message","topic":"/spotMarket/tradeOrders","subject":"orderChange","channelType":"private","data":{"symbol":"BTC-USDT","orderType":"limit","side":"buy","liquidity":"maker","orderId":"6487e5c4e2c3b40001a4f28e","type":"match","orderTime":1686627780125000000,"size":"1","filledSize":"1","price":"26000","matchPrice":"26000","matchSize":"0.5","tradeId":"6487e5f22d9e7d0001a4d3c2","clientOid":"c-1","remainSize":"0","status":"match","ts":1686627826190000000}}"#,
+        r#"{"type":"message","topic":"/spotMarket/tradeOrders","subject":"orderChange","channelType":"private","data":{"symbol":"BTC-USDT","orderType":"limit","side":"buy","orderId":"6487e5c4e2c3b40001a4f28e","type":"filled","orderTime":1686627780125000000,"size":"1","filledSize":"1","price":"26000","clientOid":"c-1","remainSize":"0","status":"done","ts":1686627826190000000}}"#,
+        r#"{"type":"message","topic":"/spotMarket/tradeOrders","subject":"orderChange","channelType":"private","data":{"symbol":"BTC-USDT","orderType":"limit","side":"buy","orderId":"6487e5c4e2c3b40001a4f28e","type":"canceled","orderTime":1686627780125000000,"size":"1","filledSize":"1","price":"26000","clientOid":"c-1","remainSize":"0","status":"done","ts":1686627826200000000}}"#,
+    ];
+
+    #[tokio::test]
+    async fn applies_trade_order_events() {
+        let orders = OrderManager::new();
+        let mut transitions = orders.transitions();
+        orders.track(TrackedOrder::new("c-1", "BTC-USDT", "buy"));
+        assert!(orders.mark_accepted("unknown", "x").is_err());
+        assert!(orders.mark_rejected("unknown", "x").is_err());
+        assert!(orders.get("unknown").is_none());
+
+        for raw in TRADE_ORDERS {
+            orders.apply(&parse_message(Message::Text(raw.to_string())).unwrap());
+        }
+        let done = orders.wait_until_done("c-1").await.unwrap();
+        assert_eq!(done.state, OrderState::Filled);
+        assert_eq!(done.order_id.as_deref(), Some("6487e5c4e2c3b40001a4f28e"));
+
+        // No New -> New transition on track, terminal states are final
+        let states: Vec<OrderState> = (0..3).map(|_| transitions.try_recv().unwrap().to).collect();
+        assert_eq!(states, vec![OrderState::Open, OrderState::PartiallyFilled, OrderState::Filled]);
+        assert!(transitions.try_recv().is_err());
+    }
+
+    /// `GET /api/v1/orders/{orderId}` response for the order of TRADE_ORDERS after its first match.
+    const ORDER_INFO: &str = r#"{"code":"200000","data":{"id":"6487e5c4e2c3b40001a4f28e","symbol":"BTC-USDT","opType":"DEAL","type":"limit","side":"buy","price":"26000","size":"1","funds":"0","dealFunds":"13000","dealSize":"0.5","fee":"0","feeCurrency":"USDT","stp":"","stop":"","stopTriggered":false,"stopPrice":"0","timeInForce":"GTC","postOnly":false,"hidden":false,"iceberg":false,"visibleSize":"0","cancelAfter":0,"channel":"API","clientOid":"c-1","remark":null,"tags":null,"isActive":true,"cancelExist":false,"createdAt":1686627780125,"tradeType":"TRADE"}}"#;
+
+    #[test]
+    fn keeps_timestamps_in_nanoseconds() {
+        let orders = OrderManager::new();
+        orders.track(TrackedOrder::new("c-1", "BTC-USDT", "buy"));
+        for raw in &TRADE_ORDERS[..2] {
+            orders.apply(&parse_message(Message::Text(raw.to_string())).unwrap());
+        }
+        assert_eq!(orders.get("c-1").unwrap().ts, 1686627826184000000);
+
+        // The snapshot only carries its creation time, which must not move ts back.
+        let info: APIDatum<OrderInfo> = serde_json::from_str(ORDER_INFO).unwrap();
+        let mut info = info.data.unwrap();
+        orders.apply_order_info(&info);
+        let order = orders.get("c-1").unwrap();
+        assert_eq!((order.ts, order.state), (1686627826184000000, OrderState::PartiallyFilled));
+
+        info.last_updated_at = Some(1686627830000);
+        orders.apply_order_info(&info);
+        assert_eq!(orders.get("c-1").unwrap().ts, 1686627830000000000);
+
+        assert_eq!(partial_or_open("0.00000001"), OrderState::PartiallyFilled);
+        assert_eq!(partial_or_open("0.00000000"), OrderState::Open);
+        assert_eq!(partial_or_open(""), OrderState::Open);
+    }
+
+    #[test]
+    fn reconciles_only_orders_missing_from_active_list() {
+        let orders = OrderManager::new();
+        orders.track(TrackedOrder::new("c-1", "BTC-USDT", "buy"));
+        orders.track(TrackedOrder::new("c-2", "BTC-USDT", "sell"));
+        orders.track(TrackedOrder::new("c-3", "BTC-USDT", "sell"));
+        orders.mark_accepted("c-1", "o-1").unwrap();
+        orders.mark_accepted("c-2", "o-2").unwrap();
+
+        let active: HashSet<String> = vec!["o-1".to_string()].into_iter().collect();
+        assert_eq!(orders.missing_from(&active), vec!["o-2".to_string()]);
+    }
+}
@@ -0,0 +1,366 @@
+//! Websocket market data recording and replay
+//!
+//! [`WSRecorder`] writes every raw frame received by a [`KucoinWebsocket`] to a JSON lines file,
+//! optionally gzip compressed, together with its local receive time. [`WSReplayer`] reads such a file back and yields
+//! the same `Result<KucoinWebsocketMsg>` items the live socket produced, either at the recorded pace,
+//! at a multiple of it, or as fast as the consumer can poll.
+//!
+//! [`KucoinWebsocket`]: crate::websocket::KucoinWebsocket
+
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::mpsc;
+use std::task::Context;
+use std::task::Poll;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use futures::prelude::*;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::error::Result;
+use crate::model::websocket::KucoinWebsocketMsg;
+use crate::utils::get_time;
+use crate::websocket::parse_message;
+
+/// Lines buffered between the reading thread and a [`WSReplayer`].
+const REPLAY_BUFFER: usize = 1024;
+
+/// A single recorded websocket frame. `ts` is the local receive time in milliseconds.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedFrame {
+    pub ts: u64,
+    pub frame: Frame,
+}
+
+/// Raw websocket frame payload. Binary payloads are stored base64 encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", content = "data", rename_all = "camelCase")]
+pub enum Frame {
+    Text(String),
+    Binary(String),
+    Ping,
+    Pong,
+    Close,
+}
+
+impl From<&Message> for Frame {
+    fn from(msg: &Message) -> Self {
+        match msg {
+            Message::Text(t) => Frame::Text(t.to_owned()),
+            Message::Binary(b) => Frame::Binary(base64::encode(b)),
+            Message::Ping(_) => Frame::Ping,
+            Message::Pong(_) => Frame::Pong,
+            Message::Close(_) => Frame::Close,
+        }
+    }
+}
+
+impl Frame {
+    pub fn into_message(self) -> Result<Message> {
+        let msg = match self {
+            Frame::Text(t) => Message::Text(t),
+            Frame::Binary(b) => Message::Binary(base64::decode(b).map_err(anyhow::Error::from)?),
+            Frame::Ping => Message::Ping(vec![]),
+            Frame::Pong => Message::Pong(vec![]),
+            Frame::Close => Message::Close(None),
+        };
+        Ok(msg)
+    }
+}
+
+/// Compression of a recording file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+}
+
+impl Compression {
+    /// Gzip for paths ending in `.gz`, none otherwise.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext == "gz" => Compression::Gzip,
+            _ => Compression::None,
+        }
+    }
+}
+
+enum Sink {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl Sink {
+    fn new(file: File, compression: Compression) -> Self {
+        let writer = BufWriter::new(file);
+        match compression {
+            Compression::None => Sink::Plain(writer),
+            Compression::Gzip => Sink::Gzip(GzEncoder::new(writer, flate2::Compression::default())),
+        }
+    }
+
+    fn finish(self) -> std::io::Result<()> {
+        match self {
+            Sink::Plain(mut w) => w.flush(),
+            Sink::Gzip(w) => w.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Sink::Plain(w) => w.write(buf),
+            Sink::Gzip(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Sink::Plain(w) => w.flush(),
+            Sink::Gzip(w) => w.flush(),
+        }
+    }
+}
+
+enum Command {
+    Frame(RecordedFrame),
+    Flush(mpsc::Sender<std::io::Result<()>>),
+}
+
+/// Runs on the writer thread until the recorder is dropped or a write fails.
+fn write_frames(mut sink: Sink, commands: mpsc::Receiver<Command>) -> Result<()> {
+    for command in commands {
+        match command {
+            Command::Frame(frame) => {
+                serde_json::to_writer(&mut sink, &frame)?;
+                sink.write_all(b"\n")?;
+            },
+            Command::Flush(ack) => {
+                let _ = ack.send(sink.flush());
+            },
+        }
+    }
+    sink.finish()?;
+    Ok(())
+}
+
+/// Writes raw websocket frames to a JSON lines file, gzip compressed if the path ends in `.gz`.
+/// Attach to a socket with [`KucoinWebsocket::record`](crate::websocket::KucoinWebsocket::record).
+///
+/// Frames are written by a dedicated thread, so recording never blocks the socket. Call
+/// [`WSRecorder::finish`] to make sure every frame reached the file, which for gzip files also
+/// writes the trailer.
+///
+/// Example:
+/// ``` ignore
+/// use kucoin_api::recorder::WSRecorder;
+///
+///     let mut ws = api.websocket();
+///     ws.record(WSRecorder::create("btc-usdt.jsonl.gz")?);
+///     ws.subscribe(url, vec![WSTopic::Match(vec!["BTC-USDT".to_string()])]).await?;
+/// ```
+#[derive(Debug)]
+pub struct WSRecorder {
+    commands: mpsc::Sender<Command>,
+    writer: JoinHandle<Result<()>>,
+}
+
+impl WSRecorder {
+    /// Creates (or truncates) the file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::create(&path)?;
+        Ok(WSRecorder::spawn(file, Compression::from_path(path.as_ref())))
+    }
+
+    /// Appends to the file at `path`, creating it if needed. Gzip files get a new gzip member,
+    /// which [`WSReplayer`] reads transparently.
+    pub fn append(path: impl AsRef<Path>) -> Result<Self> {
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(WSRecorder::spawn(file, Compression::from_path(path.as_ref())))
+    }
+
+    fn spawn(file: File, compression: Compression) -> Self {
+        let (commands, rx) = mpsc::channel();
+        let sink = Sink::new(file, compression);
+        let writer = std::thread::spawn(move || write_frames(sink, rx));
+        WSRecorder { commands, writer }
+    }
+
+    /// Records a frame stamped with the current time.
+    pub fn record(&mut self, msg: &Message) -> Result<()> {
+        self.record_at(get_time() as u64, msg)
+    }
+
+    /// Records a frame with an explicit receive time in milliseconds. Fails once the writer
+    /// thread stopped on a write error, see [`WSRecorder::finish`] for the error itself.
+    pub fn record_at(&mut self, ts: u64, msg: &Message) -> Result<()> {
+        let frame = RecordedFrame { ts, frame: Frame::from(msg) };
+        match self.commands.send(Command::Frame(frame)) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(anyhow!("Recorder writer stopped"))?,
+        }
+    }
+
+    /// Blocks until every frame recorded so far has been handed to the file.
+    pub fn flush(&mut self) -> Result<()> {
+        let (ack, done) = mpsc::channel();
+        if self.commands.send(Command::Flush(ack)).is_err() {
+            Err(anyhow!("Recorder writer stopped"))?
+        }
+        match done.recv() {
+            Ok(result) => Ok(result?),
+            Err(_) => Err(anyhow!("Recorder writer stopped"))?,
+        }
+    }
+
+    /// Writes the remaining frames, closes the file and returns the first write error, if any.
+    pub fn finish(self) -> Result<()> {
+        drop(self.commands);
+        match self.writer.join() {
+            Ok(result) => result,
+            Err(_) => Err(anyhow!("Recorder writer panicked"))?,
+        }
+    }
+}
+
+/// Pace at which a [`WSReplayer`] yields recorded frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Reproduces the original gaps between frames.
+    Realtime,
+    /// Divides the original gaps by the given factor, e.g. `10.0` replays ten times faster.
+    Multiplier(f64),
+    /// Yields frames as fast as they are polled.
+    Unthrottled,
+}
+
+impl ReplaySpeed {
+    fn delay(&self, gap_ms: u64) -> Option<Duration> {
+        match self {
+            ReplaySpeed::Realtime => Some(Duration::from_millis(gap_ms)),
+            ReplaySpeed::Multiplier(m) if *m > 0.0 => Some(Duration::from_secs_f64(gap_ms as f64 / 1000.0 / m)),
+            ReplaySpeed::Multiplier(_) | ReplaySpeed::Unthrottled => None,
+        }
+    }
+}
+
+/// Replays a file written by [`WSRecorder`] as a `Stream<Item = Result<KucoinWebsocketMsg>>`,
+/// decoding each frame exactly as [`KucoinWebsocket`](crate::websocket::KucoinWebsocket) does.
+///
+/// Example:
+/// ``` ignore
+/// use kucoin_api::recorder::{ReplaySpeed, WSReplayer};
+///
+///     let mut replay = WSReplayer::open("btc-usdt.jsonl", ReplaySpeed::Unthrottled).await?;
+///     while let Some(msg) = replay.try_next().await? {
+///         println!("{:#?}", msg);
+///     }
+/// ```
+pub struct WSReplayer {
+    inner: Pin<Box<dyn Stream<Item = Result<KucoinWebsocketMsg>> + Send>>,
+}
+
+impl WSReplayer {
+    /// Opens a recording, decompressing it if the path ends in `.gz`.
+    pub async fn open(path: impl AsRef<Path>, speed: ReplaySpeed) -> Result<Self> {
+        let file = File::open(&path)?;
+        let reader: Box<dyn BufRead + Send> = match Compression::from_path(path.as_ref()) {
+            Compression::None => Box::new(BufReader::new(file)),
+            Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(file))),
+        };
+        let (tx, rx) = tokio::sync::mpsc::channel(REPLAY_BUFFER);
+        std::thread::spawn(move || {
+            for line in reader.lines() {
+                let failed = line.is_err();
+                if tx.blocking_send(line).is_err() || failed {
+                    break;
+                }
+            }
+        });
+        let inner = stream::try_unfold((rx, None::<u64>), move |(mut rx, last_ts)| async move {
+            loop {
+                let line = match rx.recv().await {
+                    Some(l) => l?,
+                    None => return Ok(None),
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let recorded: RecordedFrame = serde_json::from_str(&line)?;
+                if let Some(delay) = last_ts.and_then(|t| speed.delay(recorded.ts.saturating_sub(t))) {
+                    tokio::time::sleep(delay).await;
+                }
+                return Ok(Some((recorded.frame, (rx, Some(recorded.ts)))));
+            }
+        })
+        .and_then(|frame| async move { frame.into_message().and_then(parse_message) });
+        Ok(WSReplayer { inner: Box::pin(inner) })
+    }
+}
+
+impl Stream for WSReplayer {
+    type Item = Result<KucoinWebsocketMsg>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::TryStreamExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    use crate::model::websocket::KucoinWebsocketMsg;
+    use crate::recorder::ReplaySpeed;
+    use crate::recorder::WSRecorder;
+    use crate::recorder::WSReplayer;
+
+    #[tokio::test]
+    async fn record_and_replay_round_trip() {
+        let path = std::env::temp_dir().join(format!("kucoin_api_recorder_{}.jsonl", std::process::id()));
+        let mut recorder = WSRecorder::create(&path).unwrap();
+        recorder.record_at(1_000, &Message::Text(r#"{"id":"1","type":"welcome"}"#.to_string())).unwrap();
+        recorder.record_at(1_010, &Message::Text(r#"{"id":"2","type":"pong"}"#.to_string())).unwrap();
+        recorder.record_at(1_020, &Message::Binary(vec![1, 2, 3])).unwrap();
+        recorder.flush().unwrap();
+
+        let replay = WSReplayer::open(&path, ReplaySpeed::Unthrottled).await.unwrap();
+        let msgs: Vec<KucoinWebsocketMsg> = replay.try_collect().await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(msgs.len(), 3);
+        assert!(matches!(&msgs[0], KucoinWebsocketMsg::WelcomeMsg(m) if m.id == "1"));
+        assert!(matches!(&msgs[1], KucoinWebsocketMsg::PongMsg(m) if m.id == "2"));
+        assert!(matches!(&msgs[2], KucoinWebsocketMsg::Binary(b) if b == &vec![1, 2, 3]));
+    }
+
+    #[tokio::test]
+    async fn record_and_replay_gzip() {
+        let path = std::env::temp_dir().join(format!("kucoin_api_recorder_{}.jsonl.gz", std::process::id()));
+        let mut recorder = WSRecorder::create(&path).unwrap();
+        recorder.record_at(1_000, &Message::Text(r#"{"id":"1","type":"welcome"}"#.to_string())).unwrap();
+        recorder.finish().unwrap();
+        let mut recorder = WSRecorder::append(&path).unwrap();
+        recorder.record_at(1_010, &Message::Text(r#"{"id":"2","type":"pong"}"#.to_string())).unwrap();
+        recorder.finish().unwrap();
+        assert_eq!(&std::fs::read(&path).unwrap()[..2], &[0x1f, 0x8b]);
+
+        let replay = WSReplayer::open(&path, ReplaySpeed::Unthrottled).await.unwrap();
+        let msgs: Vec<KucoinWebsocketMsg> = replay.try_collect().await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(msgs.len(), 2);
+        assert!(matches!(&msgs[1], KucoinWebsocketMsg::PongMsg(m) if m.id == "2"));
+    }
+}
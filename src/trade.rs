@@ -1,6 +1,13 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 
+use anyhow::anyhow;
 use reqwest::header;
+use serde_json::json;
+use serde_json::Value;
 
 use super::client::Kucoin;
 use super::error::Error;
@@ -8,13 +15,20 @@ use super::model::trade::CancelByClientOidResp;
 use super::model::trade::CancelResp;
 use super::model::trade::FillsInfo;
 use super::model::trade::HistoricalOrder;
+use super::model::trade::MultiOrderResp;
+use super::model::trade::MultiOrderResult;
 use super::model::trade::OrderInfo;
 use super::model::trade::OrderResp;
+use super::model::trade::StopOrderInfo;
 use super::model::APIData;
 use super::model::APIDatum;
 use super::model::Method;
 use super::model::Pagination;
 use super::utils::format_query;
+use super::utils::get_time;
+
+/// Maximum number of orders accepted by a single `/api/v1/orders/multi` request.
+pub const MULTI_ORDER_MAX: usize = 5;
 
 impl Kucoin {
     /// Places a limit order. Takes required inputs directly and a Some<OrderOptionals> type, or
@@ -29,8 +43,6 @@ impl Kucoin {
         size: &str,
         optionals: Option<OrderOptionals<'_>>,
     ) -> Result<APIDatum<OrderResp>, Error> {
-        let endpoint = String::from("/api/v1/orders");
-        let url = format!("{}{}", &self.prefix, endpoint);
         let mut params: HashMap<String, String> = HashMap::new();
         params.insert(String::from("clientOid"), client_oid.to_string());
         params.insert(String::from("symbol"), symbol.to_string());
@@ -41,11 +53,57 @@ impl Kucoin {
             let opts = parse_order(opt);
             params.extend(opts);
         };
+        let endpoint = self.order_endpoint("/api/v1/orders", &params);
+        let url = format!("{}{}", &self.prefix, endpoint);
         let headers: header::HeaderMap = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
         let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
         Ok(resp)
     }
 
+    /// Places a batch of limit orders for a single symbol. Requests are split into chunks of
+    /// MULTI_ORDER_MAX and sent in order. One result is returned per request, in the same order:
+    /// a chunk rejected as a whole, or one whose request never reached the exchange, is reported
+    /// as failed results carrying the error message instead of aborting the remaining chunks. A
+    /// chunk whose request may have reached the exchange but got no readable response is reported
+    /// with the `unknown` status: those orders may be live, check them with
+    /// `get_order_by_client_oid` before retrying.
+    pub async fn post_multiple_orders(
+        &self,
+        symbol: &str,
+        orders: Vec<OrderRequest<'_>>,
+    ) -> Result<Vec<MultiOrderResult>, Error> {
+        let endpoint = String::from("/api/v1/orders/multi");
+        self.ensure_live(&endpoint, &orders)?;
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let mut results = Vec::with_capacity(orders.len());
+        for chunk in orders.chunks(MULTI_ORDER_MAX) {
+            let body = multi_order_body(symbol, chunk).to_string();
+            let headers: header::HeaderMap = self.sign_headers_json(endpoint.clone(), &body, Method::POST)?;
+            let resp: Result<APIDatum<MultiOrderResp>, Error> =
+                match self.post_json(url.clone(), Some(headers), body).await {
+                    Ok(r) => r.json().await.map_err(Error::from),
+                    Err(e) => Err(e),
+                };
+            match resp {
+                Ok(APIDatum { data: Some(d), .. }) => results.extend(d.data),
+                Ok(APIDatum { code, msg, .. }) => {
+                    let msg = format!("{}: {}", code, msg.unwrap_or_default());
+                    results.extend(chunk.iter().map(|o| o.failed(symbol, &msg)));
+                },
+                Err(e) if may_have_reached_exchange(&e) => {
+                    tracing::warn!(%e, "Multi order request failed, order state unknown");
+                    let msg = e.to_string();
+                    results.extend(chunk.iter().map(|o| o.result(symbol, MultiOrderResult::UNKNOWN, &msg)));
+                },
+                Err(e) => {
+                    let msg = e.to_string();
+                    results.extend(chunk.iter().map(|o| o.failed(symbol, &msg)));
+                },
+            }
+        }
+        Ok(results)
+    }
+
     /// Places a market order. Takes required inputs directly and a Some<OrderOptionals> type, or
     /// None for optional inputs. See OrderOptionals for build pattern usage to simplify
     /// generating optional params.
@@ -62,8 +120,6 @@ impl Kucoin {
         funds: Option<f32>,
         optionals: Option<OrderOptionals<'_>>,
     ) -> Result<APIDatum<OrderResp>, Error> {
-        let endpoint = String::from("/api/v1/orders");
-        let url = format!("{}{}", &self.prefix, endpoint);
         let mut params: HashMap<String, String> = HashMap::new();
         params.insert(String::from("clientOid"), client_oid.to_string());
         params.insert(String::from("symbol"), symbol.to_string());
@@ -79,6 +135,8 @@ impl Kucoin {
             let opts = parse_order(opt);
             params.extend(opts);
         };
+        let endpoint = self.order_endpoint("/api/v1/orders", &params);
+        let url = format!("{}{}", &self.prefix, endpoint);
         let headers: header::HeaderMap = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
         let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
         Ok(resp)
@@ -93,7 +151,7 @@ impl Kucoin {
         Ok(resp)
     }
 
-    /// Cancels an order based on the provided order id (required).
+    /// Cancels an order based on the provided client order id (required).
     pub async fn cancel_order_by_client_oid(&self, client_oid: &str) -> Result<APIDatum<CancelByClientOidResp>, Error> {
         let endpoint = format!("/api/v1/order/client-order/{}", client_oid);
         let url = format!("{}{}", &self.prefix, endpoint);
@@ -239,6 +297,15 @@ impl Kucoin {
         Ok(resp)
     }
 
+    /// Looks up an order, spot or margin, by the client order id it was placed with.
+    pub async fn get_order_by_client_oid(&self, client_oid: &str) -> Result<APIDatum<OrderInfo>, Error> {
+        let endpoint = format!("/api/v1/order/client-order/{}", client_oid);
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers: header::HeaderMap = self.sign_headers(endpoint, None, None, Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
     pub async fn get_fills(
         &self,
         optionals: Option<FillsOptionals<'_>>,
@@ -295,14 +362,235 @@ impl Kucoin {
         let resp = self.get(url, Some(headers)).await?.json().await?;
         Ok(resp)
     }
+
+    /// Places a stop limit order. `stop` is either `loss` (triggers when the price falls to or
+    /// below `stop_price`) or `entry` (triggers when the price rises to or above it). See
+    /// OrderOptionals for the remaining optional inputs, its `stop`/`stop_price` are ignored here.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn post_stop_limit_order(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        stop: &str,
+        stop_price: &str,
+        price: &str,
+        size: &str,
+        optionals: Option<OrderOptionals<'_>>,
+    ) -> Result<APIDatum<OrderResp>, Error> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("type"), String::from("limit"));
+        params.insert(String::from("price"), price.to_string());
+        params.insert(String::from("size"), size.to_string());
+        self.post_stop_order(client_oid, symbol, side, stop, stop_price, params, optionals).await
+    }
+
+    /// Places a stop market order. As with post_market_order only one of size (base currency) or
+    /// funds (quote currency) should be given.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn post_stop_market_order(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        stop: &str,
+        stop_price: &str,
+        size: Option<&str>,
+        funds: Option<&str>,
+        optionals: Option<OrderOptionals<'_>>,
+    ) -> Result<APIDatum<OrderResp>, Error> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("type"), String::from("market"));
+        if let Some(s) = size {
+            params.insert(String::from("size"), s.to_string());
+        };
+        if let Some(f) = funds {
+            params.insert(String::from("funds"), f.to_string());
+        };
+        self.post_stop_order(client_oid, symbol, side, stop, stop_price, params, optionals).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn post_stop_order(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        stop: &str,
+        stop_price: &str,
+        order_params: HashMap<String, String>,
+        optionals: Option<OrderOptionals<'_>>,
+    ) -> Result<APIDatum<OrderResp>, Error> {
+        let endpoint = String::from("/api/v1/stop-order");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let mut params: HashMap<String, String> = HashMap::new();
+        if let Some(opt) = optionals {
+            if let Some(t) = opt.trade_type {
+                params.insert(String::from("tradeType"), t.to_string());
+            };
+            params.extend(parse_order(opt));
+        };
+        params.extend(order_params);
+        params.insert(String::from("clientOid"), client_oid.to_string());
+        params.insert(String::from("symbol"), symbol.to_string());
+        params.insert(String::from("side"), side.to_string());
+        params.insert(String::from("stop"), stop.to_string());
+        params.insert(String::from("stopPrice"), stop_price.to_string());
+        self.ensure_live(&endpoint, &params)?;
+        let headers: header::HeaderMap = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
+        Ok(resp)
+    }
+
+    pub async fn cancel_stop_order(&self, order_id: &str) -> Result<APIDatum<CancelResp>, Error> {
+        let endpoint = format!("/api/v1/stop-order/{}", order_id);
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers: header::HeaderMap = self.sign_headers(endpoint, None, None, Method::DELETE)?;
+        let resp = self.delete(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    pub async fn cancel_stop_order_by_client_oid(
+        &self,
+        client_oid: &str,
+        symbol: Option<&str>,
+    ) -> Result<APIDatum<CancelByClientOidResp>, Error> {
+        let endpoint = String::from("/api/v1/stop-order/cancelOrderByClientOid");
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("clientOid"), client_oid.to_string());
+        if let Some(s) = symbol {
+            params.insert(String::from("symbol"), s.to_string());
+        };
+        let query = format_query(&params);
+        let url = format!("{}{}{}", &self.prefix, endpoint, query);
+        let headers: header::HeaderMap = self.sign_headers(endpoint, None, Some(query), Method::DELETE)?;
+        let resp = self.delete(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Cancels stop orders in bulk. Without any filter every untriggered stop order is canceled.
+    pub async fn cancel_stop_orders(
+        &self,
+        symbol: Option<&str>,
+        trade_type: Option<&str>,
+        order_ids: Option<&[&str]>,
+    ) -> Result<APIDatum<CancelResp>, Error> {
+        let endpoint = String::from("/api/v1/stop-order/cancel");
+        let url: String;
+        let headers: header::HeaderMap;
+        let mut params: HashMap<String, String> = HashMap::new();
+        if let Some(s) = symbol {
+            params.insert(String::from("symbol"), s.to_owned());
+        };
+        if let Some(t) = trade_type {
+            params.insert(String::from("tradeType"), t.to_owned());
+        };
+        if let Some(ids) = order_ids {
+            params.insert(String::from("orderIds"), ids.join(","));
+        };
+        if !params.is_empty() {
+            let query = format_query(&params);
+            url = format!("{}{}{}", &self.prefix, endpoint, query);
+            headers = self.sign_headers(endpoint, None, Some(query), Method::DELETE)?;
+        } else {
+            url = format!("{}{}", &self.prefix, endpoint);
+            headers = self.sign_headers(endpoint, None, None, Method::DELETE)?;
+        };
+        let resp = self.delete(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Lists untriggered stop orders. See StopOrderOptionals for the available filters.
+    pub async fn get_stop_orders(
+        &self,
+        optionals: Option<StopOrderOptionals<'_>>,
+    ) -> Result<APIDatum<Pagination<StopOrderInfo>>, Error> {
+        let endpoint = String::from("/api/v1/stop-order");
+        let url: String;
+        let headers: header::HeaderMap;
+        let mut params: HashMap<String, String> = HashMap::new();
+        if let Some(opts) = optionals {
+            if let Some(o) = opts.symbol {
+                params.insert("symbol".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.side {
+                params.insert("side".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.r#type {
+                params.insert("type".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.trade_type {
+                params.insert("tradeType".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.start_at {
+                params.insert("startAt".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.end_at {
+                params.insert("endAt".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.current_page {
+                params.insert("currentPage".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.page_size {
+                params.insert("pageSize".to_string(), o.to_string());
+            };
+            if let Some(o) = opts.order_ids {
+                params.insert("orderIds".to_string(), o.to_string());
+            };
+        };
+        if !params.is_empty() {
+            let query = format_query(&params);
+            url = format!("{}{}{}", &self.prefix, endpoint, query);
+            headers = self.sign_headers(endpoint, None, Some(query), Method::GET)?;
+        } else {
+            url = format!("{}{}", &self.prefix, endpoint);
+            headers = self.sign_headers(endpoint, None, None, Method::GET)?;
+        };
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    pub async fn get_stop_order(&self, order_id: &str) -> Result<APIDatum<StopOrderInfo>, Error> {
+        let endpoint = format!("/api/v1/stop-order/{}", order_id);
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers: header::HeaderMap = self.sign_headers(endpoint, None, None, Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    pub async fn get_stop_order_by_client_oid(
+        &self,
+        client_oid: &str,
+        symbol: Option<&str>,
+    ) -> Result<APIData<StopOrderInfo>, Error> {
+        let endpoint = String::from("/api/v1/stop-order/queryOrderByClientOid");
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("clientOid"), client_oid.to_string());
+        if let Some(s) = symbol {
+            params.insert(String::from("symbol"), s.to_string());
+        };
+        let query = format_query(&params);
+        let url = format!("{}{}{}", &self.prefix, endpoint, query);
+        let headers: header::HeaderMap = self.sign_headers(endpoint, None, Some(query), Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
 }
 
-fn parse_order(optionals: OrderOptionals) -> HashMap<String, String> {
+fn multi_order_body(symbol: &str, orders: &[OrderRequest]) -> Value {
+    let order_list: Vec<HashMap<String, String>> = orders.iter().map(|o| o.to_params()).collect();
+    json!({ "symbol": symbol, "orderList": order_list })
+}
+
+pub(crate) fn parse_order(optionals: OrderOptionals) -> HashMap<String, String> {
     let mut params: HashMap<String, String> = HashMap::new();
 
     if let Some(o) = optionals.remark {
         params.insert(String::from("remark"), o.to_string());
     };
+    if let Some(o) = optionals.stp {
+        params.insert(String::from("stp"), o.to_string());
+    };
     if let Some(o) = optionals.stop {
         params.insert(String::from("stop"), o.to_string());
     };
@@ -456,6 +744,164 @@ impl<'a> OrderOptionals<'a> {
     }
 }
 
+/// A single limit order of a batch placed with `post_multiple_orders`.
+///
+/// Example:
+/// ``` rust
+/// use kucoin_api::trade::OrderOptionals;
+/// use kucoin_api::trade::OrderRequest;
+///
+///     let orders = vec![
+///         OrderRequest::new("bid-1", "buy", "29000", "0.01", None),
+///         OrderRequest::new("bid-2", "buy", "28900", "0.01", Some(OrderOptionals::new().post_only(true).build())),
+///     ];
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderRequest<'a> {
+    pub client_oid: &'a str,
+    pub side: &'a str,
+    pub price: &'a str,
+    pub size: &'a str,
+    pub optionals: Option<OrderOptionals<'a>>,
+}
+
+impl<'a> OrderRequest<'a> {
+    pub fn new(
+        client_oid: &'a str,
+        side: &'a str,
+        price: &'a str,
+        size: &'a str,
+        optionals: Option<OrderOptionals<'a>>,
+    ) -> Self {
+        OrderRequest { client_oid, side, price, size, optionals }
+    }
+
+    pub(crate) fn to_params(&self) -> HashMap<String, String> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("clientOid"), self.client_oid.to_string());
+        params.insert(String::from("side"), self.side.to_string());
+        params.insert(String::from("type"), String::from("limit"));
+        params.insert(String::from("price"), self.price.to_string());
+        params.insert(String::from("size"), self.size.to_string());
+        if let Some(opt) = self.optionals.clone() {
+            params.extend(parse_order(opt));
+        }
+        params
+    }
+
+    fn failed(&self, symbol: &str, msg: &str) -> MultiOrderResult {
+        self.result(symbol, MultiOrderResult::FAIL, msg)
+    }
+
+    fn result(&self, symbol: &str, status: &str, msg: &str) -> MultiOrderResult {
+        MultiOrderResult {
+            symbol: symbol.to_string(),
+            side: self.side.to_string(),
+            price: Some(self.price.to_string()),
+            size: Some(self.size.to_string()),
+            id: None,
+            client_oid: Some(self.client_oid.to_string()),
+            status: status.to_string(),
+            fail_msg: Some(msg.to_string()),
+        }
+    }
+}
+
+/// Whether a failed order request may still have been processed by the exchange. Only requests
+/// that failed to connect or to be built are known not to have been sent.
+pub(crate) fn may_have_reached_exchange(e: &Error) -> bool {
+    !matches!(e, Error::HTTP(e) if e.is_connect() || e.is_builder())
+}
+
+/// Maximum length of a client order id accepted by the exchange.
+pub const CLIENT_OID_MAX_LEN: usize = 40;
+/// Maximum length of the prefix given to ClientOid::generate.
+pub const CLIENT_OID_PREFIX_MAX_LEN: usize = CLIENT_OID_MAX_LEN - 21;
+
+static CLIENT_OID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Validated client order id: at most CLIENT_OID_MAX_LEN letters, digits, `-` or `_`.
+///
+/// `generate` builds ids from a prefix, the current time in milliseconds, the process id and a
+/// process wide counter, so ids generated in the same millisecond, or by several processes sharing
+/// a prefix, do not collide.
+///
+/// Example:
+/// ``` rust
+/// use kucoin_api::trade::ClientOid;
+///
+///     let oid = ClientOid::generate("grid").unwrap();
+///     assert!(oid.as_str().starts_with("grid-"));
+///     let parsed: ClientOid = "my-order-1".parse().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ClientOid(String);
+
+impl ClientOid {
+    /// Generates a new id starting with `prefix`, which may be empty and must not exceed
+    /// CLIENT_OID_PREFIX_MAX_LEN characters.
+    pub fn generate(prefix: &str) -> Result<Self, Error> {
+        if prefix.len() > CLIENT_OID_PREFIX_MAX_LEN {
+            Err(anyhow!("Client oid prefix longer than {} characters: {}", CLIENT_OID_PREFIX_MAX_LEN, prefix))?
+        }
+        let count = CLIENT_OID_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let unique =
+            format!("{}{}{}", base36(get_time() as u64, 9), base36(u64::from(std::process::id()), 7), base36(count, 4));
+        if prefix.is_empty() {
+            unique.parse()
+        } else {
+            format!("{}-{}", prefix, unique).parse()
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for ClientOid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        if s.is_empty() || s.len() > CLIENT_OID_MAX_LEN {
+            Err(anyhow!("Client oid must be 1 to {} characters long: {}", CLIENT_OID_MAX_LEN, s))?
+        }
+        if !s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            Err(anyhow!("Client oid may only contain letters, digits, - and _: {}", s))?
+        }
+        Ok(ClientOid(s.to_string()))
+    }
+}
+
+impl fmt::Display for ClientOid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for ClientOid {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<ClientOid> for String {
+    fn from(oid: ClientOid) -> Self {
+        oid.0
+    }
+}
+
+/// Lowercase base 36 representation of the last `width` digits of `n`, zero padded.
+fn base36(mut n: u64, width: usize) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut out = vec![b'0'; width];
+    for c in out.iter_mut().rev() {
+        *c = DIGITS[(n % 36) as usize];
+        n /= 36;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
 /// OrderInfoOptionals contains a builder pattern that can be used to more easily take advantage of
 /// optional inputs.
 ///
@@ -667,11 +1113,200 @@ impl<'a> FillsOptionals<'a> {
     }
 }
 
+/// StopOrderOptionals contains a builder pattern for the filters of get_stop_orders.
+///
+/// Example:
+/// ``` rust
+/// use kucoin_api::trade::StopOrderOptionals;
+///
+///     let options = StopOrderOptionals::new()
+///         .symbol("BTC-USDT")
+///         .side("sell")
+///         .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StopOrderOptionals<'a> {
+    pub symbol: Option<&'a str>,
+    pub side: Option<&'a str>,
+    pub r#type: Option<&'a str>,
+    pub trade_type: Option<&'a str>,
+    pub start_at: Option<i64>,
+    pub end_at: Option<i64>,
+    pub current_page: Option<i32>,
+    pub page_size: Option<i32>,
+    /// Comma separated list of order ids
+    pub order_ids: Option<&'a str>,
+}
+
+impl<'a> StopOrderOptionals<'a> {
+    pub fn new() -> Self {
+        StopOrderOptionals {
+            symbol: None,
+            side: None,
+            r#type: None,
+            trade_type: None,
+            start_at: None,
+            end_at: None,
+            current_page: None,
+            page_size: None,
+            order_ids: None,
+        }
+    }
+
+    pub fn symbol(&mut self, s: &'a str) -> &mut Self {
+        self.symbol = Some(s);
+        self
+    }
+
+    pub fn side(&mut self, s: &'a str) -> &mut Self {
+        self.side = Some(s);
+        self
+    }
+
+    pub fn order_type(&mut self, s: &'a str) -> &mut Self {
+        self.r#type = Some(s);
+        self
+    }
+
+    pub fn trade_type(&mut self, s: &'a str) -> &mut Self {
+        self.trade_type = Some(s);
+        self
+    }
+
+    pub fn start_at(&mut self, i: i64) -> &mut Self {
+        self.start_at = Some(i);
+        self
+    }
+
+    pub fn end_at(&mut self, i: i64) -> &mut Self {
+        self.end_at = Some(i);
+        self
+    }
+
+    pub fn current_page(&mut self, i: i32) -> &mut Self {
+        self.current_page = Some(i);
+        self
+    }
+
+    pub fn page_size(&mut self, i: i32) -> &mut Self {
+        self.page_size = Some(i);
+        self
+    }
+
+    pub fn order_ids(&mut self, s: &'a str) -> &mut Self {
+        self.order_ids = Some(s);
+        self
+    }
+
+    pub fn build(&self) -> Self {
+        StopOrderOptionals {
+            symbol: self.symbol,
+            side: self.side,
+            r#type: self.r#type,
+            trade_type: self.trade_type,
+            start_at: self.start_at,
+            end_at: self.end_at,
+            current_page: self.current_page,
+            page_size: self.page_size,
+            order_ids: self.order_ids,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use crate::error::Error;
+    use crate::model::trade::MultiOrderResult;
+    use crate::trade::base36;
+    use crate::trade::may_have_reached_exchange;
+    use crate::trade::multi_order_body;
+    use crate::trade::ClientOid;
     use crate::trade::FillsOptionals;
     use crate::trade::OrderInfoOptionals;
     use crate::trade::OrderOptionals;
+    use crate::trade::OrderRequest;
+    use crate::trade::StopOrderOptionals;
+    use crate::trade::CLIENT_OID_MAX_LEN;
+    use crate::trade::MULTI_ORDER_MAX;
+
+    #[test]
+    fn generates_unique_bounded_client_oids() {
+        assert_eq!(base36(35, 3), "00z");
+        assert_eq!(base36(36 * 36 * 36, 3), "000");
+        let prefix = "a".repeat(19);
+        let a = ClientOid::generate(&prefix).unwrap();
+        let b = ClientOid::generate(&prefix).unwrap();
+        assert_ne!(a, b);
+        assert_eq!(a.as_str().len(), CLIENT_OID_MAX_LEN);
+        assert!(ClientOid::generate(&"a".repeat(20)).is_err());
+        assert_eq!(ClientOid::generate("").unwrap().as_str().len(), 20);
+        assert!("a b".parse::<ClientOid>().is_err());
+        assert!("x".repeat(41).parse::<ClientOid>().is_err());
+        assert_eq!("my_order-1".parse::<ClientOid>().unwrap().to_string(), "my_order-1");
+    }
+
+    #[tokio::test]
+    async fn dry_run_routes_orders_to_test_endpoints() {
+        use std::collections::HashMap;
+
+        use crate::client::Kucoin;
+        use crate::client::KucoinEnv;
+
+        let params: HashMap<String, String> = HashMap::new();
+        let api = Kucoin::new(KucoinEnv::Sandbox, None).unwrap();
+        assert!(!api.is_dry_run());
+        assert_eq!(api.order_endpoint("/api/v1/orders", &params), "/api/v1/orders");
+        assert!(api.ensure_live("/api/v1/orders/multi", &params).is_ok());
+        let dry = api.dry_run();
+        assert_eq!(dry.order_endpoint("/api/v1/orders", &params), "/api/v1/orders/test");
+        assert_eq!(dry.order_endpoint("/api/v1/margin/order", &params), "/api/v1/margin/order/test");
+        assert!(dry.ensure_live("/api/v1/orders/multi", &params).is_err());
+
+        // HF alters cancel and replace live orders, they must not go out in dry run
+        let dry_run_refused = |e: crate::error::Error| format!("{:?}", e).contains("dry run");
+        let altered = dry.alter_hf_order("BTC-USDT", "6487e5c4e2c3b40001a4f28e", Some("29000"), None).await;
+        assert!(altered.is_err_and(dry_run_refused));
+        let altered = dry.alter_hf_order_by_client_oid("BTC-USDT", "c-1", None, Some("0.01")).await;
+        assert!(altered.is_err_and(dry_run_refused));
+    }
+
+    #[tokio::test]
+    async fn unsent_and_unanswered_orders_are_told_apart() {
+        let unsent = reqwest::get("http://127.0.0.1:1").await.unwrap_err();
+        assert!(!may_have_reached_exchange(&Error::HTTP(unsent)));
+        let undecodable = serde_json::from_str::<serde_json::Value>("<html>").unwrap_err();
+        assert!(may_have_reached_exchange(&Error::Serde(undecodable)));
+
+        let order = OrderRequest::new("oid-1", "buy", "1.5", "2", None);
+        let unknown = order.result("BTC-USDT", MultiOrderResult::UNKNOWN, "timed out");
+        assert!(unknown.is_unknown() && !unknown.is_success());
+        assert_eq!(unknown.client_oid.as_deref(), Some("oid-1"));
+        assert!(!order.failed("BTC-USDT", "rejected").is_unknown());
+    }
+
+    #[test]
+    fn multi_order_body_and_chunking() {
+        let oids: Vec<String> = (0..12).map(|i| format!("oid-{}", i)).collect();
+        let orders: Vec<OrderRequest> = oids
+            .iter()
+            .map(|oid| {
+                OrderRequest::new(oid, "buy", "1.5", "2", Some(OrderOptionals::new().post_only(true).stp("CO").build()))
+            })
+            .collect();
+        let chunks: Vec<usize> = orders.chunks(MULTI_ORDER_MAX).map(|c| c.len()).collect();
+        assert_eq!(chunks, vec![5, 5, 2]);
+
+        let body = multi_order_body("BTC-USDT", &orders[..2]);
+        assert_eq!(body["symbol"], "BTC-USDT");
+        let list = body["orderList"].as_array().unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[1]["clientOid"], "oid-1");
+        assert_eq!(list[1]["type"], "limit");
+        assert_eq!(list[1]["postOnly"], "true");
+        assert_eq!(list[0]["stp"], "CO");
+        assert_eq!(list[1]["stp"], "CO");
+    }
+
     #[test]
     fn use_build_pattern_all_order_optionals() {
         let options = OrderOptionals {
@@ -830,4 +1465,29 @@ mod test {
 
         assert_eq!(options, build_options)
     }
+
+    #[test]
+    fn use_build_pattern_some_stop_order_optionals() {
+        let options = StopOrderOptionals {
+            symbol: Some("BTC-USDT"),
+            side: Some("sell"),
+            r#type: None,
+            trade_type: Some("TRADE"),
+            start_at: None,
+            end_at: None,
+            current_page: Some(2),
+            page_size: None,
+            order_ids: Some("id-1,id-2"),
+        };
+
+        let build_options = StopOrderOptionals::new()
+            .symbol("BTC-USDT")
+            .side("sell")
+            .trade_type("TRADE")
+            .current_page(2)
+            .order_ids("id-1,id-2")
+            .build();
+
+        assert_eq!(options, build_options)
+    }
 }
@@ -0,0 +1,579 @@
+//! High-frequency (HF) spot trading
+//!
+//! HF orders are placed against the `trade_hf` account ([`AccountType::TradeHf`]) through the
+//! `/api/v1/hf/orders` endpoints. Besides the plain place/cancel calls, the `_sync` variants wait
+//! for the matching engine and return the resulting order state, orders can be modified in place
+//! and a `cancel-all-after` countdown can be armed as a dead man's switch.
+//!
+//! [`AccountType::TradeHf`]: crate::model::user::AccountType::TradeHf
+
+use std::collections::HashMap;
+
+use reqwest::header;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use super::client::Kucoin;
+use super::model::trade_hf::CancelAllAfterInfo;
+use super::model::trade_hf::CancelAllAfterResp;
+use super::model::trade_hf::HfActiveSymbols;
+use super::model::trade_hf::HfAlterResp;
+use super::model::trade_hf::HfCancelAllResp;
+use super::model::trade_hf::HfCancelByClientOidResp;
+use super::model::trade_hf::HfCancelResp;
+use super::model::trade_hf::HfFill;
+use super::model::trade_hf::HfMultiOrderResult;
+use super::model::trade_hf::HfOrderInfo;
+use super::model::trade_hf::HfOrderResp;
+use super::model::trade_hf::HfPage;
+use super::model::trade_hf::HfSyncCancelResp;
+use super::model::trade_hf::HfSyncOrderResp;
+use super::model::APIData;
+use super::model::APIDatum;
+use super::model::Method;
+use super::trade::may_have_reached_exchange;
+use super::trade::parse_order;
+use super::trade::OrderOptionals;
+use super::trade::OrderRequest;
+use super::utils::format_query;
+use crate::error::Result;
+
+/// Maximum number of orders accepted by a single `/api/v1/hf/orders/multi` request.
+pub const HF_MULTI_ORDER_MAX: usize = 20;
+
+impl Kucoin {
+    /// Places an HF limit order. See OrderOptionals for the optional inputs.
+    pub async fn post_hf_limit_order(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        price: &str,
+        size: &str,
+        optionals: Option<OrderOptionals<'_>>,
+    ) -> Result<APIDatum<HfOrderResp>> {
+        let params = hf_limit_params(client_oid, symbol, side, price, size, optionals);
+        self.post_hf(self.order_endpoint("/api/v1/hf/orders", &params), params).await
+    }
+
+    /// Places an HF market order. Only one of size (base currency) or funds (quote currency)
+    /// should be given.
+    pub async fn post_hf_market_order(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        size: Option<&str>,
+        funds: Option<&str>,
+        optionals: Option<OrderOptionals<'_>>,
+    ) -> Result<APIDatum<HfOrderResp>> {
+        let params = hf_market_params(client_oid, symbol, side, size, funds, optionals);
+        self.post_hf(self.order_endpoint("/api/v1/hf/orders", &params), params).await
+    }
+
+    /// Same as post_hf_limit_order but only returns once the order has been processed by the
+    /// matching engine, with its resulting fill state.
+    pub async fn post_hf_limit_order_sync(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        price: &str,
+        size: &str,
+        optionals: Option<OrderOptionals<'_>>,
+    ) -> Result<APIDatum<HfSyncOrderResp>> {
+        let params = hf_limit_params(client_oid, symbol, side, price, size, optionals);
+        self.ensure_live("/api/v1/hf/orders/sync", &params)?;
+        self.post_hf(String::from("/api/v1/hf/orders/sync"), params).await
+    }
+
+    /// Same as post_hf_market_order but only returns once the order has been processed by the
+    /// matching engine, with its resulting fill state.
+    pub async fn post_hf_market_order_sync(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+        side: &str,
+        size: Option<&str>,
+        funds: Option<&str>,
+        optionals: Option<OrderOptionals<'_>>,
+    ) -> Result<APIDatum<HfSyncOrderResp>> {
+        let params = hf_market_params(client_oid, symbol, side, size, funds, optionals);
+        self.ensure_live("/api/v1/hf/orders/sync", &params)?;
+        self.post_hf(String::from("/api/v1/hf/orders/sync"), params).await
+    }
+
+    /// Places a batch of HF limit orders, split into chunks of HF_MULTI_ORDER_MAX. One result is
+    /// returned per request, in order. Chunks rejected as a whole or whose request never reached
+    /// the exchange are reported as failed results carrying the error message. Chunks whose
+    /// request may have reached the exchange but got no readable response are reported with
+    /// `unknown` set: those orders may be live, check them by clientOid before retrying.
+    pub async fn post_hf_multiple_orders(
+        &self,
+        symbol: &str,
+        orders: Vec<OrderRequest<'_>>,
+    ) -> Result<Vec<HfMultiOrderResult>> {
+        let endpoint = String::from("/api/v1/hf/orders/multi");
+        self.ensure_live(&endpoint, &orders)?;
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let mut results = Vec::with_capacity(orders.len());
+        for chunk in orders.chunks(HF_MULTI_ORDER_MAX) {
+            let order_list: Vec<HashMap<String, String>> = chunk
+                .iter()
+                .map(|o| {
+                    let mut params = o.to_params();
+                    params.insert(String::from("symbol"), symbol.to_string());
+                    params
+                })
+                .collect();
+            let body = json!({ "orderList": order_list }).to_string();
+            let headers: header::HeaderMap = self.sign_headers_json(endpoint.clone(), &body, Method::POST)?;
+            let resp: Result<APIData<HfMultiOrderResult>> = match self.post_json(url.clone(), Some(headers), body).await
+            {
+                Ok(r) => r.json().await.map_err(Into::into),
+                Err(e) => Err(e),
+            };
+            let failed = |msg: String, unknown: bool| -> Vec<HfMultiOrderResult> {
+                chunk
+                    .iter()
+                    .map(|o| HfMultiOrderResult {
+                        order_id: None,
+                        client_oid: Some(o.client_oid.to_string()),
+                        success: false,
+                        fail_msg: Some(msg.clone()),
+                        unknown,
+                    })
+                    .collect()
+            };
+            match resp {
+                Ok(APIData { data: Some(d), .. }) => results.extend(d),
+                Ok(APIData { code, msg, .. }) => {
+                    results.extend(failed(format!("{}: {}", code, msg.unwrap_or_default()), false))
+                },
+                Err(e) if may_have_reached_exchange(&e) => {
+                    tracing::warn!(%e, "HF multi order request failed, order state unknown");
+                    results.extend(failed(e.to_string(), true))
+                },
+                Err(e) => results.extend(failed(e.to_string(), false)),
+            }
+        }
+        Ok(results)
+    }
+
+    /// Modifies the price and/or size of an open HF order. The exchange cancels the order and
+    /// places a new one, whose id is returned.
+    pub async fn alter_hf_order(
+        &self,
+        symbol: &str,
+        order_id: &str,
+        new_price: Option<&str>,
+        new_size: Option<&str>,
+    ) -> Result<APIDatum<HfAlterResp>> {
+        let params = hf_alter_params(symbol, "orderId", order_id, new_price, new_size);
+        self.ensure_live("/api/v1/hf/orders/alter", &params)?;
+        self.post_hf(String::from("/api/v1/hf/orders/alter"), params).await
+    }
+
+    pub async fn alter_hf_order_by_client_oid(
+        &self,
+        symbol: &str,
+        client_oid: &str,
+        new_price: Option<&str>,
+        new_size: Option<&str>,
+    ) -> Result<APIDatum<HfAlterResp>> {
+        let params = hf_alter_params(symbol, "clientOid", client_oid, new_price, new_size);
+        self.ensure_live("/api/v1/hf/orders/alter", &params)?;
+        self.post_hf(String::from("/api/v1/hf/orders/alter"), params).await
+    }
+
+    pub async fn cancel_hf_order(&self, order_id: &str, symbol: &str) -> Result<APIDatum<HfCancelResp>> {
+        let endpoint = format!("/api/v1/hf/orders/{}", order_id);
+        self.query_hf(endpoint, symbol_param(symbol), Method::DELETE).await
+    }
+
+    /// Cancels an HF order and waits for the matching engine to confirm the cancellation.
+    pub async fn cancel_hf_order_sync(&self, order_id: &str, symbol: &str) -> Result<APIDatum<HfSyncCancelResp>> {
+        let endpoint = format!("/api/v1/hf/orders/sync/{}", order_id);
+        self.query_hf(endpoint, symbol_param(symbol), Method::DELETE).await
+    }
+
+    pub async fn cancel_hf_order_by_client_oid(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+    ) -> Result<APIDatum<HfCancelByClientOidResp>> {
+        let endpoint = format!("/api/v1/hf/orders/client-order/{}", client_oid);
+        self.query_hf(endpoint, symbol_param(symbol), Method::DELETE).await
+    }
+
+    pub async fn cancel_hf_order_by_client_oid_sync(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+    ) -> Result<APIDatum<HfSyncCancelResp>> {
+        let endpoint = format!("/api/v1/hf/orders/sync/client-order/{}", client_oid);
+        self.query_hf(endpoint, symbol_param(symbol), Method::DELETE).await
+    }
+
+    /// Cancels every open HF order of a symbol.
+    pub async fn cancel_hf_orders(&self, symbol: &str) -> Result<APIDatum<String>> {
+        self.query_hf(String::from("/api/v1/hf/orders"), symbol_param(symbol), Method::DELETE).await
+    }
+
+    /// Cancels every open HF order across all symbols.
+    pub async fn cancel_all_hf_orders(&self) -> Result<APIDatum<HfCancelAllResp>> {
+        self.query_hf(String::from("/api/v1/hf/orders/cancelAll"), HashMap::new(), Method::DELETE).await
+    }
+
+    pub async fn get_hf_active_orders(&self, symbol: &str) -> Result<APIData<HfOrderInfo>> {
+        self.query_hf(String::from("/api/v1/hf/orders/active"), symbol_param(symbol), Method::GET).await
+    }
+
+    /// Lists the symbols with open HF orders.
+    pub async fn get_hf_active_symbols(&self) -> Result<APIDatum<HfActiveSymbols>> {
+        self.query_hf(String::from("/api/v1/hf/orders/active/symbols"), HashMap::new(), Method::GET).await
+    }
+
+    /// Lists filled and canceled HF orders of a symbol. See HfHistoryOptionals for the filters.
+    pub async fn get_hf_done_orders(
+        &self,
+        symbol: &str,
+        optionals: Option<HfHistoryOptionals<'_>>,
+    ) -> Result<APIDatum<HfPage<HfOrderInfo>>> {
+        let mut params = symbol_param(symbol);
+        if let Some(opts) = optionals {
+            params.extend(opts.to_params());
+        };
+        self.query_hf(String::from("/api/v1/hf/orders/done"), params, Method::GET).await
+    }
+
+    pub async fn get_hf_order(&self, order_id: &str, symbol: &str) -> Result<APIDatum<HfOrderInfo>> {
+        let endpoint = format!("/api/v1/hf/orders/{}", order_id);
+        self.query_hf(endpoint, symbol_param(symbol), Method::GET).await
+    }
+
+    pub async fn get_hf_order_by_client_oid(&self, client_oid: &str, symbol: &str) -> Result<APIDatum<HfOrderInfo>> {
+        let endpoint = format!("/api/v1/hf/orders/client-order/{}", client_oid);
+        self.query_hf(endpoint, symbol_param(symbol), Method::GET).await
+    }
+
+    /// Looks up an HF margin order by client order id.
+    pub async fn get_hf_margin_order_by_client_oid(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+    ) -> Result<APIDatum<HfOrderInfo>> {
+        let endpoint = format!("/api/v3/hf/margin/orders/client-order/{}", client_oid);
+        self.query_hf(endpoint, symbol_param(symbol), Method::GET).await
+    }
+
+    pub async fn cancel_hf_margin_order_by_client_oid(
+        &self,
+        client_oid: &str,
+        symbol: &str,
+    ) -> Result<APIDatum<HfCancelByClientOidResp>> {
+        let endpoint = format!("/api/v3/hf/margin/orders/client-order/{}", client_oid);
+        self.query_hf(endpoint, symbol_param(symbol), Method::DELETE).await
+    }
+
+    /// Lists HF fills of a symbol. See HfHistoryOptionals for the filters.
+    pub async fn get_hf_fills(
+        &self,
+        symbol: &str,
+        optionals: Option<HfHistoryOptionals<'_>>,
+    ) -> Result<APIDatum<HfPage<HfFill>>> {
+        let mut params = symbol_param(symbol);
+        if let Some(opts) = optionals {
+            params.extend(opts.to_params());
+        };
+        self.query_hf(String::from("/api/v1/hf/fills"), params, Method::GET).await
+    }
+
+    /// Arms (or re-arms) the `cancel-all-after` countdown: unless called again within `timeout`
+    /// seconds, every HF order of `symbols` (all symbols if None) is canceled. A `timeout` of -1
+    /// disarms it.
+    pub async fn set_hf_cancel_all_after(
+        &self,
+        timeout: i64,
+        symbols: Option<&[&str]>,
+    ) -> Result<APIDatum<CancelAllAfterResp>> {
+        let endpoint = String::from("/api/v1/hf/orders/dead-cancel-all");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let body = match symbols {
+            Some(s) => json!({ "timeout": timeout, "symbols": s.join(",") }),
+            None => json!({ "timeout": timeout }),
+        }
+        .to_string();
+        let headers: header::HeaderMap = self.sign_headers_json(endpoint, &body, Method::POST)?;
+        let resp = self.post_json(url, Some(headers), body).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Returns the armed `cancel-all-after` countdown, `data` is None when it is not armed.
+    pub async fn get_hf_cancel_all_after(&self) -> Result<APIDatum<CancelAllAfterInfo>> {
+        self.query_hf(String::from("/api/v1/hf/orders/dead-cancel-all/query"), HashMap::new(), Method::GET).await
+    }
+
+    async fn post_hf<T: DeserializeOwned>(&self, endpoint: String, params: HashMap<String, String>) -> Result<T> {
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers: header::HeaderMap = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
+        Ok(resp)
+    }
+
+    async fn query_hf<T: DeserializeOwned>(
+        &self,
+        endpoint: String,
+        params: HashMap<String, String>,
+        method: Method,
+    ) -> Result<T> {
+        let query = format_query(&params);
+        let url = format!("{}{}{}", &self.prefix, endpoint, query);
+        let query = if query.is_empty() { None } else { Some(query) };
+        let resp = match method {
+            Method::DELETE => {
+                let headers: header::HeaderMap = self.sign_headers(endpoint, None, query, Method::DELETE)?;
+                self.delete(url, Some(headers)).await?
+            },
+            _ => {
+                let headers: header::HeaderMap = self.sign_headers(endpoint, None, query, Method::GET)?;
+                self.get(url, Some(headers)).await?
+            },
+        };
+        Ok(resp.json().await?)
+    }
+}
+
+fn symbol_param(symbol: &str) -> HashMap<String, String> {
+    let mut params: HashMap<String, String> = HashMap::new();
+    params.insert(String::from("symbol"), symbol.to_string());
+    params
+}
+
+fn hf_order_params(
+    client_oid: &str,
+    symbol: &str,
+    side: &str,
+    optionals: Option<OrderOptionals<'_>>,
+) -> HashMap<String, String> {
+    let mut params: HashMap<String, String> = HashMap::new();
+    if let Some(opt) = optionals {
+        params.extend(parse_order(opt));
+    };
+    params.insert(String::from("clientOid"), client_oid.to_string());
+    params.insert(String::from("symbol"), symbol.to_string());
+    params.insert(String::from("side"), side.to_string());
+    params
+}
+
+fn hf_limit_params(
+    client_oid: &str,
+    symbol: &str,
+    side: &str,
+    price: &str,
+    size: &str,
+    optionals: Option<OrderOptionals<'_>>,
+) -> HashMap<String, String> {
+    let mut params = hf_order_params(client_oid, symbol, side, optionals);
+    params.insert(String::from("type"), String::from("limit"));
+    params.insert(String::from("price"), price.to_string());
+    params.insert(String::from("size"), size.to_string());
+    params
+}
+
+fn hf_market_params(
+    client_oid: &str,
+    symbol: &str,
+    side: &str,
+    size: Option<&str>,
+    funds: Option<&str>,
+    optionals: Option<OrderOptionals<'_>>,
+) -> HashMap<String, String> {
+    let mut params = hf_order_params(client_oid, symbol, side, optionals);
+    params.insert(String::from("type"), String::from("market"));
+    if let Some(s) = size {
+        params.insert(String::from("size"), s.to_string());
+    };
+    if let Some(f) = funds {
+        params.insert(String::from("funds"), f.to_string());
+    };
+    params
+}
+
+fn hf_alter_params(
+    symbol: &str,
+    id_key: &str,
+    id: &str,
+    new_price: Option<&str>,
+    new_size: Option<&str>,
+) -> HashMap<String, String> {
+    let mut params = symbol_param(symbol);
+    params.insert(id_key.to_string(), id.to_string());
+    if let Some(p) = new_price {
+        params.insert(String::from("newPrice"), p.to_string());
+    };
+    if let Some(s) = new_size {
+        params.insert(String::from("newSize"), s.to_string());
+    };
+    params
+}
+
+/// HfHistoryOptionals contains a builder pattern for the filters of get_hf_done_orders and
+/// get_hf_fills. `order_id` only applies to fills.
+///
+/// Example:
+/// ``` rust
+/// use kucoin_api::trade_hf::HfHistoryOptionals;
+///
+///     let options = HfHistoryOptionals::new()
+///         .side("buy")
+///         .limit(50)
+///         .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HfHistoryOptionals<'a> {
+    pub order_id: Option<&'a str>,
+    pub side: Option<&'a str>,
+    pub r#type: Option<&'a str>,
+    pub start_at: Option<i64>,
+    pub end_at: Option<i64>,
+    pub last_id: Option<i64>,
+    pub limit: Option<i32>,
+}
+
+impl<'a> HfHistoryOptionals<'a> {
+    pub fn new() -> Self {
+        HfHistoryOptionals {
+            order_id: None,
+            side: None,
+            r#type: None,
+            start_at: None,
+            end_at: None,
+            last_id: None,
+            limit: None,
+        }
+    }
+
+    pub fn order_id(&mut self, s: &'a str) -> &mut Self {
+        self.order_id = Some(s);
+        self
+    }
+
+    pub fn side(&mut self, s: &'a str) -> &mut Self {
+        self.side = Some(s);
+        self
+    }
+
+    pub fn order_type(&mut self, s: &'a str) -> &mut Self {
+        self.r#type = Some(s);
+        self
+    }
+
+    pub fn start_at(&mut self, i: i64) -> &mut Self {
+        self.start_at = Some(i);
+        self
+    }
+
+    pub fn end_at(&mut self, i: i64) -> &mut Self {
+        self.end_at = Some(i);
+        self
+    }
+
+    pub fn last_id(&mut self, i: i64) -> &mut Self {
+        self.last_id = Some(i);
+        self
+    }
+
+    pub fn limit(&mut self, i: i32) -> &mut Self {
+        self.limit = Some(i);
+        self
+    }
+
+    pub fn build(&self) -> Self {
+        HfHistoryOptionals {
+            order_id: self.order_id,
+            side: self.side,
+            r#type: self.r#type,
+            start_at: self.start_at,
+            end_at: self.end_at,
+            last_id: self.last_id,
+            limit: self.limit,
+        }
+    }
+
+    fn to_params(&self) -> HashMap<String, String> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        if let Some(o) = self.order_id {
+            params.insert("orderId".to_string(), o.to_string());
+        };
+        if let Some(o) = self.side {
+            params.insert("side".to_string(), o.to_string());
+        };
+        if let Some(o) = self.r#type {
+            params.insert("type".to_string(), o.to_string());
+        };
+        if let Some(o) = self.start_at {
+            params.insert("startAt".to_string(), o.to_string());
+        };
+        if let Some(o) = self.end_at {
+            params.insert("endAt".to_string(), o.to_string());
+        };
+        if let Some(o) = self.last_id {
+            params.insert("lastId".to_string(), o.to_string());
+        };
+        if let Some(o) = self.limit {
+            params.insert("limit".to_string(), o.to_string());
+        };
+        params
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::model::trade_hf::HfMultiOrderResult;
+    use crate::model::APIData;
+    use crate::trade::OrderOptionals;
+    use crate::trade_hf::hf_limit_params;
+    use crate::trade_hf::HfHistoryOptionals;
+
+    #[test]
+    fn use_build_pattern_hf_history_optionals() {
+        let options = HfHistoryOptionals {
+            order_id: None,
+            side: Some("buy"),
+            r#type: Some("limit"),
+            start_at: None,
+            end_at: None,
+            last_id: Some(254_062_248_624_417),
+            limit: Some(50),
+        };
+
+        let build_options =
+            HfHistoryOptionals::new().side("buy").order_type("limit").last_id(254_062_248_624_417).limit(50).build();
+
+        assert_eq!(options, build_options);
+        assert_eq!(build_options.to_params().get("lastId").map(String::as_str), Some("254062248624417"));
+    }
+
+    #[test]
+    fn hf_multi_order_results() {
+        let resp: APIData<HfMultiOrderResult> = serde_json::from_str(
+            r#"{"code":"200000","data":[{"orderId":"6487e5c4e2c3b40001a4f28e","clientOid":"c-1","success":true},
+            {"clientOid":"c-2","success":false,"failMsg":"The order funds should more then 0.1 USDT."}]}"#,
+        )
+        .unwrap();
+        let results = resp.data.unwrap();
+        assert!(results[0].success && !results[0].unknown);
+        assert!(!results[1].success && !results[1].unknown);
+    }
+
+    #[test]
+    fn hf_limit_params_keep_required_inputs() {
+        let opts = OrderOptionals::new().stp("CN").post_only(true).build();
+        let params = hf_limit_params("oid", "BTC-USDT", "buy", "29000", "0.01", Some(opts));
+        assert_eq!(params["type"], "limit");
+        assert_eq!(params["stp"], "CN");
+        assert_eq!(params["postOnly"], "true");
+        assert_eq!(params["price"], "29000");
+    }
+}
@@ -1,20 +1,41 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 
+use anyhow::anyhow;
 use reqwest::header;
+use rust_decimal::Decimal;
+use rust_decimal::RoundingStrategy;
 
 use super::client::Kucoin;
 use super::error::Error;
+use super::model::market::ChainId;
+use super::model::market::CurrencyChain;
 use super::model::user::AccountHolds;
 use super::model::user::AccountId;
 use super::model::user::AccountInfo;
+use super::model::user::AccountSummary;
 use super::model::user::AccountType;
 use super::model::user::Accounts;
+use super::model::user::ApiKeyInfo;
+use super::model::user::ApiKeyPermission;
+use super::model::user::BaseFee;
 use super::model::user::DepositAddress;
+use super::model::user::DepositAddressV3;
 use super::model::user::DepositList;
 use super::model::user::DepositListV1;
 use super::model::user::OrderId;
 use super::model::user::SingleAccount;
+use super::model::user::SubAccountAccess;
 use super::model::user::SubAccountBalances;
+use super::model::user::SubAccountCreated;
+use super::model::user::SubApiKey;
+use super::model::user::SubApiKeyCreated;
+use super::model::user::SubApiKeyDeleted;
+use super::model::user::SubApiKeyUpdated;
+use super::model::user::SweepResult;
+use super::model::user::TradeFee;
+use super::model::user::TransferHistoryType;
+use super::model::user::TransferType;
 use super::model::user::TransferableBalance;
 use super::model::user::UserInfo;
 use super::model::user::WithdrawalId;
@@ -25,8 +46,13 @@ use super::model::APIData;
 use super::model::APIDatum;
 use super::model::Method;
 use super::model::Pagination;
+use super::trade::ClientOid;
+use super::utils::format_encoded_query;
 use super::utils::format_query;
 
+/// Maximum number of symbols accepted by a single `get_trade_fees` request.
+pub const TRADE_FEES_MAX: usize = 10;
+
 impl Kucoin {
     pub async fn get_user_subaccount_info(&self) -> Result<APIData<UserInfo>, Error> {
         let endpoint = String::from("/api/v1/sub/user");
@@ -36,6 +62,138 @@ impl Kucoin {
         Ok(resp)
     }
 
+    /// User level together with the number of sub-accounts and their limits.
+    pub async fn get_account_summary(&self) -> Result<APIDatum<AccountSummary>, Error> {
+        let endpoint = String::from("/api/v2/user-info");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers = self.sign_headers(endpoint, None, None, Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Permissions, IP whitelist and creation time of the API key the client signs with.
+    pub async fn get_api_key_info(&self) -> Result<APIDatum<ApiKeyInfo>, Error> {
+        let endpoint = String::from("/api/v1/user/api-key");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let headers = self.sign_headers(endpoint, None, None, Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Fails unless the API key has exactly the `required` permissions, General being implied.
+    /// Meant to be called at startup so a strategy refuses to run with a key that can do more, or
+    /// less, than it needs.
+    pub async fn verify_api_key_permissions(&self, required: &[ApiKeyPermission]) -> Result<ApiKeyInfo, Error> {
+        let resp = self.get_api_key_info().await?;
+        let info = match resp.data {
+            Some(d) => d,
+            None => Err(anyhow!("Failed to get API key info: {}: {}", resp.code, resp.msg.unwrap_or_default()))?,
+        };
+        check_permissions(&info.permissions(), required)?;
+        Ok(info)
+    }
+
+    /// Creates a sub-account. `password` must be 7 to 24 characters with letters and numbers and
+    /// `sub_name` 7 to 32 characters with at least one letter and one number.
+    pub async fn create_subaccount(
+        &self,
+        sub_name: &str,
+        password: &str,
+        access: SubAccountAccess,
+        remarks: Option<&str>,
+    ) -> Result<APIDatum<SubAccountCreated>, Error> {
+        let endpoint = String::from("/api/v2/sub/user/created");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("subName"), sub_name.to_string());
+        params.insert(String::from("password"), password.to_string());
+        params.insert(String::from("access"), access.as_str().to_string());
+        if let Some(r) = remarks {
+            params.insert(String::from("remarks"), r.to_string());
+        }
+        let headers = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Lists the API keys of a sub-account, or only `api_key` if given.
+    pub async fn get_subaccount_api_keys(
+        &self,
+        sub_name: &str,
+        api_key: Option<&str>,
+    ) -> Result<APIData<SubApiKey>, Error> {
+        let endpoint = String::from("/api/v1/sub/api-key");
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("subName"), sub_name.to_string());
+        if let Some(k) = api_key {
+            params.insert(String::from("apiKey"), k.to_string());
+        }
+        let query = format_encoded_query(&params);
+        let url = format!("{}{}{}", &self.prefix, endpoint, query);
+        let headers = self.sign_headers(endpoint, None, Some(query), Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Creates an API key for a sub-account. See SubApiKeyOptionals for permissions, IP whitelist
+    /// and expiry, the key defaults to General permission only. Earn and Transfer permissions
+    /// cannot be granted to sub-account keys.
+    pub async fn create_subaccount_api_key(
+        &self,
+        sub_name: &str,
+        passphrase: &str,
+        remark: &str,
+        optionals: Option<SubApiKeyOptionals<'_>>,
+    ) -> Result<APIDatum<SubApiKeyCreated>, Error> {
+        let endpoint = String::from("/api/v1/sub/api-key");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let mut params = optionals.unwrap_or_default().to_params()?;
+        params.insert(String::from("subName"), sub_name.to_string());
+        params.insert(String::from("passphrase"), passphrase.to_string());
+        params.insert(String::from("remark"), remark.to_string());
+        let headers = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Updates the permissions, IP whitelist or expiry of a sub-account API key. Settings left
+    /// unset in `optionals` are reset to the defaults by the exchange.
+    pub async fn update_subaccount_api_key(
+        &self,
+        sub_name: &str,
+        api_key: &str,
+        passphrase: &str,
+        optionals: Option<SubApiKeyOptionals<'_>>,
+    ) -> Result<APIDatum<SubApiKeyUpdated>, Error> {
+        let endpoint = String::from("/api/v1/sub/api-key/update");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let mut params = optionals.unwrap_or_default().to_params()?;
+        params.insert(String::from("subName"), sub_name.to_string());
+        params.insert(String::from("apiKey"), api_key.to_string());
+        params.insert(String::from("passphrase"), passphrase.to_string());
+        let headers = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
+        Ok(resp)
+    }
+
+    pub async fn delete_subaccount_api_key(
+        &self,
+        sub_name: &str,
+        api_key: &str,
+        passphrase: &str,
+    ) -> Result<APIDatum<SubApiKeyDeleted>, Error> {
+        let endpoint = String::from("/api/v1/sub/api-key");
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("subName"), sub_name.to_string());
+        params.insert(String::from("apiKey"), api_key.to_string());
+        params.insert(String::from("passphrase"), passphrase.to_string());
+        let query = format_encoded_query(&params);
+        let url = format!("{}{}{}", &self.prefix, endpoint, query);
+        let headers = self.sign_headers(endpoint, None, Some(query), Method::DELETE)?;
+        let resp = self.delete(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
     pub async fn create_account(
         &self,
         account_type: AccountType,
@@ -44,11 +202,7 @@ impl Kucoin {
         let endpoint = String::from("/api/v1/accounts");
         let url = format!("{}{}", &self.prefix, endpoint);
         let mut params: HashMap<String, String> = HashMap::new();
-        match account_type {
-            AccountType::Main => params.insert(String::from("type"), String::from("main")),
-            AccountType::Margin => params.insert(String::from("type"), String::from("margin")),
-            AccountType::Trade => params.insert(String::from("type"), String::from("trade")),
-        };
+        params.insert(String::from("type"), account_type.as_str().to_string());
         params.insert(String::from("currency"), currency.to_string());
         let header = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
         let resp = self.post(url, Some(header), Some(params)).await?.json().await?;
@@ -58,7 +212,7 @@ impl Kucoin {
     pub async fn get_accounts_list(
         &self,
         currency: Option<&str>,
-        acct_type: Option<&str>,
+        acct_type: Option<AccountType>,
     ) -> Result<APIData<Accounts>, Error> {
         let mut params: HashMap<String, String> = HashMap::new();
         let headers: header::HeaderMap;
@@ -68,7 +222,7 @@ impl Kucoin {
             params.insert("currency".to_string(), c.to_owned());
         }
         if let Some(a) = acct_type {
-            params.insert("type".to_string(), a.to_owned());
+            params.insert("type".to_string(), a.as_str().to_string());
         }
         if !params.is_empty() {
             let query = format_query(&params);
@@ -175,12 +329,8 @@ impl Kucoin {
         currency: &str,
         account_type: AccountType,
     ) -> Result<APIDatum<TransferableBalance>, Error> {
-        let mut endpoint = format! {"/api/v1/accounts/transferable?currency={}", currency};
-        match account_type {
-            AccountType::Main => endpoint.push_str("&type=MAIN"),
-            AccountType::Margin => endpoint.push_str("&type=MARGIN"),
-            AccountType::Trade => endpoint.push_str("&type=TRADE"),
-        };
+        let endpoint =
+            format! {"/api/v1/accounts/transferable?currency={}&type={}", currency, account_type.as_upper_str()};
         let url = format!("{}{}", &self.prefix, endpoint);
         let headers = self.sign_headers(endpoint, None, None, Method::GET)?;
         let resp = self.get(url, Some(headers)).await?.json().await?;
@@ -195,8 +345,8 @@ impl Kucoin {
         amount: f32,
         direction: &str,
         sub_user_id: &str,
-        account_type: Option<&str>,
-        sub_account_type: Option<&str>,
+        account_type: Option<AccountType>,
+        sub_account_type: Option<AccountType>,
     ) -> Result<APIDatum<OrderId>, Error> {
         let endpoint = String::from("/api/v2/accounts/sub-transfer");
         let url = format!("{}{}", &self.prefix, endpoint);
@@ -207,10 +357,10 @@ impl Kucoin {
         params.insert(String::from("direction"), direction.to_string());
         params.insert(String::from("subUserId"), sub_user_id.to_string());
         if let Some(a) = account_type {
-            params.insert(String::from("accountType"), a.to_string());
+            params.insert(String::from("accountType"), a.as_upper_str().to_string());
         }
         if let Some(s) = sub_account_type {
-            params.insert(String::from("subAccountType"), s.to_string());
+            params.insert(String::from("subAccountType"), s.as_upper_str().to_string());
         }
         let headers = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
         let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
@@ -221,8 +371,8 @@ impl Kucoin {
         &self,
         client_oid: &str,
         currency: &str,
-        from: &str,
-        to: &str,
+        from: AccountType,
+        to: AccountType,
         amount: &str,
     ) -> Result<APIDatum<OrderId>, Error> {
         let endpoint = String::from("/api/v2/accounts/inner-transfer");
@@ -230,14 +380,156 @@ impl Kucoin {
         let mut params: HashMap<String, String> = HashMap::new();
         params.insert(String::from("clientOid"), client_oid.to_string());
         params.insert(String::from("currency"), currency.to_string());
-        params.insert(String::from("from"), from.to_string());
-        params.insert(String::from("to"), to.to_string());
+        params.insert(String::from("from"), from.as_str().to_string());
+        params.insert(String::from("to"), to.as_str().to_string());
+        params.insert(String::from("amount"), amount.to_string());
+        let headers = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Moves funds between account types and between the master and sub-accounts in a single
+    /// call. See UniversalTransferOptionals for the user ids required by master/sub transfers and
+    /// the symbol tags required by isolated margin accounts.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn universal_transfer(
+        &self,
+        client_oid: &str,
+        currency: &str,
+        amount: &str,
+        transfer_type: TransferType,
+        from: AccountType,
+        to: AccountType,
+        optionals: Option<UniversalTransferOptionals<'_>>,
+    ) -> Result<APIDatum<OrderId>, Error> {
+        let opts = optionals.unwrap_or_default();
+        let needs_tag = |a: AccountType| matches!(a, AccountType::Isolated | AccountType::IsolatedV2);
+        if needs_tag(from) && opts.from_account_tag.is_none() || needs_tag(to) && opts.to_account_tag.is_none() {
+            Err(anyhow!("Isolated margin transfers require the symbol as account tag"))?
+        }
+        match transfer_type {
+            TransferType::SubToParent if opts.from_user_id.is_none() => {
+                Err(anyhow!("Sub-account to master transfers require the sub-account user id as from user id"))?
+            },
+            TransferType::ParentToSub if opts.to_user_id.is_none() => {
+                Err(anyhow!("Master to sub-account transfers require the sub-account user id as to user id"))?
+            },
+            _ => (),
+        }
+        let endpoint = String::from("/api/v3/accounts/universal-transfer");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let mut params = opts.to_params();
+        params.insert(String::from("clientOid"), client_oid.to_string());
+        params.insert(String::from("currency"), currency.to_string());
         params.insert(String::from("amount"), amount.to_string());
+        params.insert(String::from("type"), transfer_type.as_str().to_string());
+        params.insert(String::from("fromAccountType"), from.as_upper_str().to_string());
+        params.insert(String::from("toAccountType"), to.as_upper_str().to_string());
         let headers = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
         let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
         Ok(resp)
     }
 
+    /// Lists transfer ledger entries across all accounts of the user.
+    pub async fn get_transfer_history(
+        &self,
+        history_type: TransferHistoryType,
+        currency: Option<&str>,
+        start_at: Option<i64>,
+        end_at: Option<i64>,
+        current_page: Option<i32>,
+        page_size: Option<i32>,
+    ) -> Result<APIDatum<Pagination<AccountInfo>>, Error> {
+        let endpoint = String::from("/api/v1/accounts/ledgers");
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("bizType"), history_type.as_str().to_string());
+        if let Some(c) = currency {
+            params.insert(String::from("currency"), c.to_string());
+        }
+        if let Some(t) = start_at {
+            params.insert(String::from("startAt"), t.to_string());
+        }
+        if let Some(t) = end_at {
+            params.insert(String::from("endAt"), t.to_string());
+        }
+        if let Some(c) = current_page {
+            params.insert(String::from("currentPage"), c.to_string());
+        }
+        if let Some(p) = page_size {
+            params.insert(String::from("pageSize"), p.to_string());
+        }
+        let query = format_query(&params);
+        let url = format!("{}{}{}", &self.prefix, endpoint, query);
+        let headers = self.sign_headers(endpoint, None, Some(query), Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Transfers the available `currency` balance of every sub-account's `from` account (main,
+    /// trade or margin), minus `keep`, to the master main account. One result is returned per
+    /// sub-account with something to sweep, a failed transfer does not stop the others.
+    pub async fn sweep_subaccounts(
+        &self,
+        currency: &str,
+        from: AccountType,
+        keep: Decimal,
+    ) -> Result<Vec<SweepResult>, Error> {
+        if !matches!(from, AccountType::Main | AccountType::Trade | AccountType::Margin) {
+            Err(anyhow!("Cannot sweep {} accounts", from))?
+        }
+        let resp = self.get_all_subaccount_balances().await?;
+        let subs = match resp.data {
+            Some(d) => d,
+            None => Err(anyhow!("Failed to get sub-account balances: {:?}", resp.msg))?,
+        };
+        let mut results = Vec::new();
+        for sub in subs {
+            let accounts = match from {
+                AccountType::Main => &sub.main_accounts,
+                AccountType::Trade => &sub.trade_accounts,
+                _ => &sub.margin_accounts,
+            };
+            let amount = match accounts.iter().find(|a| a.currency == currency) {
+                Some(a) => match sweep_amount(&a.available, keep) {
+                    Some(amount) => amount,
+                    None => continue,
+                },
+                None => continue,
+            };
+            let mut opts = UniversalTransferOptionals::new();
+            opts.from_user_id(&sub.sub_user_id);
+            let client_oid = ClientOid::generate("sweep")?;
+            let resp = self
+                .universal_transfer(
+                    client_oid.as_str(),
+                    currency,
+                    &amount,
+                    TransferType::SubToParent,
+                    from,
+                    AccountType::Main,
+                    Some(opts.build()),
+                )
+                .await;
+            let (order_id, fail_msg) = match resp {
+                Ok(APIDatum { data: Some(d), .. }) => (Some(d.order_id), None),
+                Ok(APIDatum { code, msg, .. }) => (None, Some(format!("{}: {}", code, msg.unwrap_or_default()))),
+                Err(e) => {
+                    tracing::warn!(%e, sub = %sub.sub_name, "Sweep transfer failed");
+                    (None, Some(e.to_string()))
+                },
+            };
+            results.push(SweepResult {
+                sub_user_id: sub.sub_user_id.clone(),
+                sub_name: sub.sub_name.clone(),
+                currency: currency.to_string(),
+                amount,
+                order_id,
+                fail_msg,
+            });
+        }
+        Ok(results)
+    }
+
     pub async fn create_deposit_address(
         &self,
         currency: &str,
@@ -274,6 +566,93 @@ impl Kucoin {
         Ok(api_data)
     }
 
+    /// All deposit addresses of `currency`, across every chain unless `chain` is given.
+    /// `amount` is only required by some Lightning Network chains.
+    pub async fn get_deposit_addresses(
+        &self,
+        currency: &str,
+        chain: Option<&ChainId>,
+        amount: Option<&str>,
+    ) -> Result<APIData<DepositAddressV3>, Error> {
+        let endpoint = String::from("/api/v3/deposit-addresses");
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("currency"), currency.to_string());
+        if let Some(c) = chain {
+            params.insert(String::from("chain"), c.to_string());
+        }
+        if let Some(a) = amount {
+            params.insert(String::from("amount"), a.to_string());
+        }
+        let query = format_query(&params);
+        let url = format!("{}{}{}", &self.prefix, endpoint, query);
+        let headers = self.sign_headers(endpoint, None, Some(query), Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?;
+        let api_data = resp.json().await?;
+        Ok(api_data)
+    }
+
+    /// Creates a deposit address on `chain`, crediting the main account unless `to` is the trade
+    /// account.
+    pub async fn create_deposit_address_v3(
+        &self,
+        currency: &str,
+        chain: &ChainId,
+        to: Option<AccountType>,
+        amount: Option<&str>,
+    ) -> Result<APIDatum<DepositAddressV3>, Error> {
+        let endpoint = String::from("/api/v3/deposit-address/create");
+        let url = format!("{}{}", &self.prefix, endpoint);
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("currency"), currency.to_string());
+        params.insert(String::from("chain"), chain.to_string());
+        match to {
+            Some(t @ (AccountType::Main | AccountType::Trade)) => {
+                params.insert(String::from("to"), t.as_str().to_string());
+            },
+            Some(t) => Err(anyhow!("Cannot deposit to {} accounts", t))?,
+            None => {},
+        }
+        if let Some(a) = amount {
+            params.insert(String::from("amount"), a.to_string());
+        }
+        let headers = self.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp = self.post(url, Some(headers), Some(params)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Deposit addresses of `currency` grouped by chain, with an entry for every chain deposits
+    /// are enabled on. With `create_missing`, an address is created on the chains that have
+    /// none yet; a failed creation is logged and leaves the chain's entry empty.
+    pub async fn get_deposit_addresses_by_chain(
+        &self,
+        currency: &str,
+        create_missing: bool,
+    ) -> Result<BTreeMap<ChainId, Vec<DepositAddressV3>>, Error> {
+        let resp = self.get_currency_detail(currency, None).await?;
+        let detail = match resp.data {
+            Some(d) => d,
+            None => Err(anyhow!("Failed to get currency {}: {:?}", currency, resp.msg))?,
+        };
+        let resp = self.get_deposit_addresses(currency, None, None).await?;
+        let addresses = match resp.data {
+            Some(d) => d,
+            None => Err(anyhow!("Failed to get deposit addresses: {:?}", resp.msg))?,
+        };
+        let mut by_chain = group_by_chain(&detail.chains, addresses);
+        if create_missing {
+            for (chain, addresses) in by_chain.iter_mut().filter(|(_, a)| a.is_empty()) {
+                match self.create_deposit_address_v3(currency, chain, None, None).await {
+                    Ok(APIDatum { data: Some(d), .. }) => addresses.push(d),
+                    Ok(APIDatum { code, msg, .. }) => {
+                        tracing::warn!(%code, ?msg, %chain, "Failed to create deposit address")
+                    },
+                    Err(e) => tracing::warn!(%e, %chain, "Failed to create deposit address"),
+                }
+            }
+        }
+        Ok(by_chain)
+    }
+
     pub async fn get_deposit_list(
         &self,
         currency: Option<&str>,
@@ -441,6 +820,8 @@ impl Kucoin {
         Ok(api_data)
     }
 
+    /// Submits a withdrawal without any checks. See WithdrawalRequest in the withdrawal module
+    /// for fractional amounts validated against the currency and quotas before submission.
     #[allow(clippy::too_many_arguments)]
     pub async fn apply_withdrawal(
         &self,
@@ -484,4 +865,357 @@ impl Kucoin {
         let api_data = resp.text().await?;
         Ok(api_data)
     }
+
+    /// Base fee rates of the account. `currency_type` is 0 for crypto quoted symbols (default)
+    /// and 1 for fiat quoted ones.
+    pub async fn get_base_fee(&self, currency_type: Option<i32>) -> Result<APIDatum<BaseFee>, Error> {
+        let endpoint = String::from("/api/v1/base-fee");
+        let mut params: HashMap<String, String> = HashMap::new();
+        if let Some(c) = currency_type {
+            params.insert(String::from("currencyType"), c.to_string());
+        }
+        let query = format_query(&params);
+        let url = format!("{}{}{}", &self.prefix, endpoint, query);
+        let headers = self.sign_headers(endpoint, None, Some(query), Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+
+    /// Actual fee rates of up to TRADE_FEES_MAX symbols. See FeeCache for a cached lookup.
+    pub async fn get_trade_fees(&self, symbols: &[&str]) -> Result<APIData<TradeFee>, Error> {
+        let endpoint = String::from("/api/v1/trade-fees");
+        let query = format!("?symbols={}", symbols.join(","));
+        let url = format!("{}{}{}", &self.prefix, endpoint, query);
+        let headers = self.sign_headers(endpoint, None, Some(query), Method::GET)?;
+        let resp = self.get(url, Some(headers)).await?.json().await?;
+        Ok(resp)
+    }
+}
+
+/// SubApiKeyOptionals contains a builder pattern for the optional settings of sub-account API keys.
+///
+/// Example:
+/// ``` rust
+/// use kucoin_api::model::user::ApiKeyPermission;
+/// use kucoin_api::user::SubApiKeyOptionals;
+///
+///     let options = SubApiKeyOptionals::new()
+///         .permissions(&[ApiKeyPermission::General, ApiKeyPermission::Spot])
+///         .ip_whitelist(&["203.0.113.7"])
+///         .expire(90)
+///         .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SubApiKeyOptionals<'a> {
+    pub permissions: Option<&'a [ApiKeyPermission]>,
+    pub ip_whitelist: Option<&'a [&'a str]>,
+    /// Validity in days: -1 (never expires), 30, 90, 180 or 360
+    pub expire: Option<i32>,
+}
+
+impl<'a> SubApiKeyOptionals<'a> {
+    pub fn new() -> Self {
+        SubApiKeyOptionals { permissions: None, ip_whitelist: None, expire: None }
+    }
+
+    pub fn permissions(&mut self, p: &'a [ApiKeyPermission]) -> &mut Self {
+        self.permissions = Some(p);
+        self
+    }
+
+    pub fn ip_whitelist(&mut self, ips: &'a [&'a str]) -> &mut Self {
+        self.ip_whitelist = Some(ips);
+        self
+    }
+
+    pub fn expire(&mut self, days: i32) -> &mut Self {
+        self.expire = Some(days);
+        self
+    }
+
+    pub fn build(&self) -> Self {
+        SubApiKeyOptionals { permissions: self.permissions, ip_whitelist: self.ip_whitelist, expire: self.expire }
+    }
+
+    fn to_params(&self) -> Result<HashMap<String, String>, Error> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        if let Some(p) = self.permissions {
+            if let Some(p) = p.iter().find(|p| matches!(p, ApiKeyPermission::Earn | ApiKeyPermission::Transfer)) {
+                Err(anyhow!("Sub-account API keys cannot be granted {} permission", p.as_str()))?
+            }
+            let p: Vec<&str> = p.iter().map(|p| p.as_str()).collect();
+            params.insert(String::from("permission"), p.join(","));
+        }
+        if let Some(ips) = self.ip_whitelist {
+            params.insert(String::from("ipWhitelist"), ips.join(","));
+        }
+        if let Some(e) = self.expire {
+            params.insert(String::from("expire"), e.to_string());
+        }
+        Ok(params)
+    }
+}
+
+/// UniversalTransferOptionals contains a builder pattern for the optional inputs of
+/// universal_transfer. User ids are required when transferring from or to a sub-account, account
+/// tags (the symbol) when transferring from or to an isolated margin account.
+///
+/// Example:
+/// ``` rust
+/// use kucoin_api::user::UniversalTransferOptionals;
+///
+///     let options = UniversalTransferOptionals::new()
+///         .to_user_id("63743f07e0c5230001761d08")
+///         .to_account_tag("BTC-USDT")
+///         .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UniversalTransferOptionals<'a> {
+    pub from_user_id: Option<&'a str>,
+    pub to_user_id: Option<&'a str>,
+    pub from_account_tag: Option<&'a str>,
+    pub to_account_tag: Option<&'a str>,
+}
+
+impl<'a> UniversalTransferOptionals<'a> {
+    pub fn new() -> Self {
+        UniversalTransferOptionals {
+            from_user_id: None,
+            to_user_id: None,
+            from_account_tag: None,
+            to_account_tag: None,
+        }
+    }
+
+    pub fn from_user_id(&mut self, id: &'a str) -> &mut Self {
+        self.from_user_id = Some(id);
+        self
+    }
+
+    pub fn to_user_id(&mut self, id: &'a str) -> &mut Self {
+        self.to_user_id = Some(id);
+        self
+    }
+
+    pub fn from_account_tag(&mut self, tag: &'a str) -> &mut Self {
+        self.from_account_tag = Some(tag);
+        self
+    }
+
+    pub fn to_account_tag(&mut self, tag: &'a str) -> &mut Self {
+        self.to_account_tag = Some(tag);
+        self
+    }
+
+    pub fn build(&self) -> Self {
+        UniversalTransferOptionals {
+            from_user_id: self.from_user_id,
+            to_user_id: self.to_user_id,
+            from_account_tag: self.from_account_tag,
+            to_account_tag: self.to_account_tag,
+        }
+    }
+
+    fn to_params(&self) -> HashMap<String, String> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        if let Some(id) = self.from_user_id {
+            params.insert(String::from("fromUserId"), id.to_string());
+        }
+        if let Some(id) = self.to_user_id {
+            params.insert(String::from("toUserId"), id.to_string());
+        }
+        if let Some(tag) = self.from_account_tag {
+            params.insert(String::from("fromAccountTag"), tag.to_string());
+        }
+        if let Some(tag) = self.to_account_tag {
+            params.insert(String::from("toAccountTag"), tag.to_string());
+        }
+        params
+    }
+}
+
+fn check_permissions(granted: &[&str], required: &[ApiKeyPermission]) -> Result<(), Error> {
+    let mut permissions: Vec<ApiKeyPermission> = Vec::new();
+    let mut unknown: Vec<&str> = Vec::new();
+    for p in granted {
+        match p.parse::<ApiKeyPermission>() {
+            Ok(ApiKeyPermission::General) => (),
+            Ok(p) => permissions.push(p),
+            Err(_) => unknown.push(p),
+        }
+    }
+    let missing: Vec<&str> = required
+        .iter()
+        .filter(|p| **p != ApiKeyPermission::General && !permissions.contains(p))
+        .map(|p| p.as_str())
+        .collect();
+    let extra: Vec<&str> = permissions.iter().filter(|p| !required.contains(p)).map(|p| p.as_str()).collect();
+    if !missing.is_empty() || !extra.is_empty() || !unknown.is_empty() {
+        Err(anyhow!(
+            "API key permissions do not match, missing: [{}], unexpected: [{}], unknown: [{}]",
+            missing.join(","),
+            extra.join(","),
+            unknown.join(",")
+        ))?
+    }
+    Ok(())
+}
+
+/// Groups addresses by chain. Every deposit enabled chain gets an entry, addresses on other
+/// chains are kept as well.
+fn group_by_chain(
+    chains: &[CurrencyChain],
+    addresses: Vec<DepositAddressV3>,
+) -> BTreeMap<ChainId, Vec<DepositAddressV3>> {
+    let mut by_chain: BTreeMap<ChainId, Vec<DepositAddressV3>> =
+        chains.iter().filter(|c| c.is_deposit_enabled).map(|c| (c.id(), Vec::new())).collect();
+    for address in addresses {
+        by_chain.entry(address.chain_id.clone()).or_default().push(address);
+    }
+    by_chain
+}
+
+/// Amount left after keeping `keep` out of `available`, rounded down to the precision of
+/// `available`. None when there is nothing to sweep.
+fn sweep_amount(available: &str, keep: Decimal) -> Option<String> {
+    let available = available.parse::<Decimal>().ok()?;
+    let amount = (available - keep).round_dp_with_strategy(available.scale(), RoundingStrategy::ToZero);
+    if amount <= Decimal::ZERO {
+        return None;
+    }
+    Some(amount.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal::Decimal;
+
+    use crate::client::Kucoin;
+    use crate::client::KucoinEnv;
+    use crate::model::market::ChainId;
+    use crate::model::market::CurrencyDetail;
+    use crate::model::user::AccountKind;
+    use crate::model::user::AccountType;
+    use crate::model::user::Accounts;
+    use crate::model::user::ApiKeyPermission;
+    use crate::model::user::DepositAddressV3;
+    use crate::model::user::TransferType;
+    use crate::model::APIData;
+    use crate::user::check_permissions;
+    use crate::user::group_by_chain;
+    use crate::user::sweep_amount;
+    use crate::user::SubApiKeyOptionals;
+
+    #[test]
+    fn use_build_pattern_sub_api_key_optionals() {
+        let permissions = [ApiKeyPermission::General, ApiKeyPermission::InnerTransfer];
+        let ips = ["203.0.113.7", "203.0.113.8"];
+        let test_struct =
+            SubApiKeyOptionals { permissions: Some(&permissions), ip_whitelist: Some(&ips), expire: None };
+        let built = SubApiKeyOptionals::new().permissions(&permissions).ip_whitelist(&ips).build();
+        assert_eq!(test_struct, built);
+        let params = built.to_params().unwrap();
+        assert_eq!(params["permission"], "General,InnerTransfer");
+        assert_eq!(params["ipWhitelist"], "203.0.113.7,203.0.113.8");
+        assert!(!params.contains_key("expire"));
+        for p in [ApiKeyPermission::Earn, ApiKeyPermission::Transfer].iter() {
+            let permissions = [ApiKeyPermission::General, *p];
+            assert!(SubApiKeyOptionals::new().permissions(&permissions).build().to_params().is_err());
+        }
+    }
+
+    #[test]
+    fn groups_deposit_addresses_by_chain() {
+        let detail: CurrencyDetail = serde_json::from_str(
+            r#"{"currency":"USDT","name":"USDT","fullName":"Tether","precision":8,"confirms":null,
+            "contractAddress":null,"isMarginEnabled":true,"isDebitEnabled":true,"chains":[
+            {"chainName":"TRC20","withdrawalMinSize":"10","depositMinSize":"1","withdrawFeeRate":"0",
+            "withdrawalMinFee":"1","isWithdrawEnabled":true,"isDepositEnabled":true,"confirms":1,"preConfirms":1,
+            "contractAddress":"TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t","withdrawPrecision":8,
+            "maxWithdraw":null,"maxDeposit":null,"needTag":false,"chainId":"trx"},
+            {"chainName":"ERC20","withdrawalMinSize":"10","depositMinSize":"1","withdrawFeeRate":"0",
+            "withdrawalMinFee":"5","isWithdrawEnabled":true,"isDepositEnabled":true,"confirms":64,"preConfirms":64,
+            "contractAddress":"0xdac17f958d2ee523a2206206994597c13d831ec7","withdrawPrecision":8,
+            "maxWithdraw":null,"maxDeposit":null,"needTag":false,"chainId":"eth"},
+            {"chainName":"OMNI","withdrawalMinSize":"10","depositMinSize":"1","withdrawFeeRate":"0",
+            "withdrawalMinFee":"5","isWithdrawEnabled":false,"isDepositEnabled":false,"confirms":2,"preConfirms":2,
+            "contractAddress":"31","withdrawPrecision":8,
+            "maxWithdraw":null,"maxDeposit":null,"needTag":false,"chainId":"btc"}]}"#,
+        )
+        .unwrap();
+        let trx = detail.chain_id("TRC20").unwrap();
+        assert_eq!(trx.as_str(), "trx");
+        assert_eq!(detail.chain_id("Solana"), None);
+        assert_eq!(detail.chain_ids().len(), 3);
+
+        let addresses: Vec<DepositAddressV3> = serde_json::from_str(
+            r#"[{"address":"T1","memo":"","chainId":"trx","to":"main","expirationDate":0,"currency":"USDT",
+            "contractAddress":"TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t","chainName":"TRC20"},
+            {"address":"T2","memo":"","chainId":"trx","to":"trade","expirationDate":0,"currency":"USDT",
+            "contractAddress":"TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t","chainName":"TRC20"}]"#,
+        )
+        .unwrap();
+        let by_chain = group_by_chain(&detail.chains, addresses);
+        assert_eq!(by_chain.len(), 2);
+        assert_eq!(by_chain[&trx].len(), 2);
+        assert!(by_chain[&detail.chain_id("eth").unwrap()].is_empty());
+        assert_eq!(serde_json::from_str::<ChainId>(r#""TRX""#).unwrap(), trx);
+    }
+
+    #[test]
+    fn accepts_unknown_account_types() {
+        let resp: APIData<Accounts> = serde_json::from_str(
+            r#"{"code":"200000","data":[
+            {"id":"5bd6e9286d99522a52e458de","currency":"BTC","type":"main","balance":"237582.04299","available":"237582.032","holds":"0.01099"},
+            {"id":"5bd6e9216d99522a52e458d6","currency":"BTC","type":"option","balance":"1","available":"1","holds":"0"}]}"#,
+        )
+        .unwrap();
+        let types: Vec<AccountKind> = resp.data.unwrap().into_iter().map(|a| a.r#type).collect();
+        assert_eq!(types, vec![AccountKind::Known(AccountType::Main), AccountKind::Other(String::from("option"))]);
+        assert_eq!(types[0], AccountType::Main);
+        assert_eq!(types[1].known(), None);
+        assert!("option".parse::<AccountType>().is_err());
+        assert!(serde_json::from_str::<AccountType>(r#""option""#).is_err());
+    }
+
+    #[test]
+    fn sweep_amount_keeps_reserve() {
+        let dec = |s: &str| s.parse::<Decimal>().unwrap();
+        assert_eq!(sweep_amount("12.5", Decimal::ZERO), Some(String::from("12.5")));
+        assert_eq!(sweep_amount("12.345678", dec("2")), Some(String::from("10.345678")));
+        assert_eq!(sweep_amount("0.3", dec("0.1")), Some(String::from("0.2")));
+        assert_eq!(sweep_amount("1.5", dec("0.25")), Some(String::from("1.2")));
+        assert_eq!(sweep_amount("1", dec("1")), None);
+        assert_eq!(sweep_amount("0.00000001", dec("0.000000001")), None);
+        assert_eq!(sweep_amount("0", Decimal::ZERO), None);
+        assert_eq!(sweep_amount("abc", Decimal::ZERO), None);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_transfers_before_sending() {
+        let api = Kucoin::new(KucoinEnv::Sandbox, None).unwrap();
+        let transfer = |transfer_type, opts| {
+            api.universal_transfer("oid", "USDT", "1", transfer_type, AccountType::Main, AccountType::Main, opts)
+        };
+        let err = format!("{:?}", transfer(TransferType::SubToParent, None).await.unwrap_err());
+        assert!(err.contains("from user id"));
+        let err = format!("{:?}", transfer(TransferType::ParentToSub, None).await.unwrap_err());
+        assert!(err.contains("to user id"));
+        let err =
+            format!("{:?}", api.sweep_subaccounts("USDT", AccountType::Isolated, Decimal::ZERO).await.unwrap_err());
+        assert!(err.contains("Cannot sweep"));
+    }
+
+    #[test]
+    fn checks_exact_api_key_permissions() {
+        let required = [ApiKeyPermission::Spot, ApiKeyPermission::Margin];
+        assert!(check_permissions(&["General", "Spot", "Margin"], &required).is_ok());
+        assert!(check_permissions(&["Margin", "Spot"], &required).is_ok());
+        let err = format!("{:?}", check_permissions(&["General", "Spot", "Transfer"], &required).unwrap_err());
+        assert!(err.contains("missing: [Margin]"));
+        assert!(err.contains("unexpected: [Transfer]"));
+        assert!(check_permissions(&["General"], &[ApiKeyPermission::General]).is_ok());
+        let err = format!("{:?}", check_permissions(&["General", "Spot", "Margin", "Options"], &required).unwrap_err());
+        assert!(err.contains("unknown: [Options]"));
+    }
 }
@@ -23,10 +23,25 @@ pub fn format_query<S: ::std::hash::BuildHasher>(params: &HashMap<String, String
     query
 }
 
+/// Formats a query like [`format_query`], percent-encoding keys and values. Use it for values
+/// that may contain reserved characters, e.g. passphrases. The same string must be signed and
+/// sent.
+pub fn format_encoded_query<S: ::std::hash::BuildHasher>(params: &HashMap<String, String, S>) -> String {
+    let mut query = String::new();
+    for (key, val) in params.iter() {
+        let key: String = url::form_urlencoded::byte_serialize(key.as_bytes()).collect();
+        let val: String = url::form_urlencoded::byte_serialize(val.as_bytes()).collect();
+        query.push(if query.is_empty() { '?' } else { '&' });
+        query.push_str(&format!("{}={}", key, val));
+    }
+    query
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
 
+    use crate::utils::format_encoded_query;
     use crate::utils::format_query;
     #[test]
     fn format_query_test() {
@@ -41,4 +56,19 @@ mod test {
         assert!(query.contains("price=124.12"));
         assert!(query.contains("quantity=0.51"));
     }
+
+    #[test]
+    fn format_encoded_query_test() {
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert("subName".to_string(), "Sub1".to_string());
+        params.insert("passphrase".to_string(), "p&ss=w+rd #1%".to_string());
+
+        let query = format_encoded_query(&params);
+        assert!(query.starts_with('?'));
+        assert!(query.contains("passphrase=p%26ss%3Dw%2Brd+%231%25"));
+        assert!(query.contains("subName=Sub1"));
+        let decoded: HashMap<String, String> =
+            url::form_urlencoded::parse(&query.as_bytes()[1..]).into_owned().collect();
+        assert_eq!(decoded, params);
+    }
 }
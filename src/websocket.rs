@@ -32,6 +32,7 @@ use crate::model::websocket::WSTopic;
 use crate::model::websocket::WSType;
 use crate::model::APIDatum;
 use crate::model::Method;
+use crate::recorder::WSRecorder;
 use crate::utils::get_time;
 
 type WSStream = WebSocketStream<tokio_tungstenite::stream::Stream<TcpStream, tokio_native_tls::TlsStream<TcpStream>>>;
@@ -44,6 +45,7 @@ pub struct KucoinWebsocket {
     tokens: HashMap<usize, WSTopic>,
     #[pin]
     streams: StreamUnordered<StoredStream>,
+    recorder: Option<WSRecorder>,
 }
 
 impl Stream for KucoinWebsocket {
@@ -53,6 +55,14 @@ impl Stream for KucoinWebsocket {
             Poll::Ready(Some((y, _))) => match y {
                 StreamYield::Item(item) => {
                     // let heartbeat = self.heartbeats.get_mut(&token)?;
+                    let recorder = self.as_mut().project().recorder;
+                    if let (Some(r), Ok(msg)) = (recorder.as_mut(), &item) {
+                        // A failing recorder must not cost the consumer live frames
+                        if let Err(e) = r.record(msg) {
+                            tracing::error!(%e, "Websocket recording failed, detaching the recorder");
+                            *recorder = None;
+                        }
+                    }
                     Poll::Ready(Some(item.map_err(Error::Websocket).and_then(parse_message)))
                 },
                 StreamYield::Finished(_) => Poll::Pending,
@@ -113,13 +123,23 @@ impl KucoinWebsocket {
         Ok(())
     }
 
+    /// Records every raw frame received from now on. See [`WSRecorder`].
+    pub fn record(&mut self, recorder: WSRecorder) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Detaches and returns the active recorder, if any.
+    pub fn stop_recording(&mut self) -> Option<WSRecorder> {
+        self.recorder.take()
+    }
+
     pub fn unsubscribe(&mut self, ws_topic: WSTopic) -> Option<StoredStream> {
         let streams = Pin::new(&mut self.streams);
         self.subscriptions.get(&ws_topic).and_then(|token| StreamUnordered::take(streams, *token))
     }
 }
 
-fn parse_message(msg: Message) -> Result<KucoinWebsocketMsg> {
+pub(crate) fn parse_message(msg: Message) -> Result<KucoinWebsocketMsg> {
     match msg {
         Message::Text(msg) => {
             if msg.contains("\"type\":\"welcome\"") || msg.contains("\"type\":\"ack\"") {
@@ -128,6 +148,8 @@ fn parse_message(msg: Message) -> Result<KucoinWebsocketMsg> {
                 Ok(KucoinWebsocketMsg::PingMsg(serde_json::from_str(&msg)?))
             } else if msg.contains("\"type\":\"pong\"") {
                 Ok(KucoinWebsocketMsg::PongMsg(serde_json::from_str(&msg)?))
+            } else if msg.contains("\"topic\":\"/contract") {
+                parse_futures_message(&msg)
             } else if msg.contains("\"subject\":\"trade.ticker\"") {
                 Ok(KucoinWebsocketMsg::TickerMsg(serde_json::from_str(&msg)?))
             } else if msg.contains("\"topic\":\"/market/ticker:all\"") {
@@ -207,6 +229,36 @@ fn parse_message(msg: Message) -> Result<KucoinWebsocketMsg> {
     }
 }
 
+// Futures messages reuse subjects of spot channels (`level2`, `match`), so they are told apart
+// by their `/contract*` topic before falling through to the spot checks.
+fn parse_futures_message(msg: &str) -> Result<KucoinWebsocketMsg> {
+    if msg.contains("\"subject\":\"tickerV2\"") {
+        Ok(KucoinWebsocketMsg::FuturesTickerMsg(serde_json::from_str(msg)?))
+    } else if msg.contains("\"subject\":\"level2\"") {
+        Ok(KucoinWebsocketMsg::FuturesLevel2Msg(serde_json::from_str(msg)?))
+    } else if msg.contains("\"subject\":\"match\"") {
+        Ok(KucoinWebsocketMsg::FuturesExecutionMsg(serde_json::from_str(msg)?))
+    } else if msg.contains("\"subject\":\"mark.index.price\"") {
+        Ok(KucoinWebsocketMsg::FuturesMarkIndexPriceMsg(serde_json::from_str(msg)?))
+    } else if msg.contains("\"subject\":\"funding.rate\"") {
+        Ok(KucoinWebsocketMsg::FuturesFundingRateMsg(serde_json::from_str(msg)?))
+    } else if msg.contains("\"subject\":\"position.change\"") {
+        Ok(KucoinWebsocketMsg::FuturesPositionChangeMsg(serde_json::from_str(msg)?))
+    } else if msg.contains("\"subject\":\"position.settlement\"") {
+        Ok(KucoinWebsocketMsg::FuturesPositionSettlementMsg(serde_json::from_str(msg)?))
+    } else if msg.contains("\"subject\":\"orderChange\"") {
+        Ok(KucoinWebsocketMsg::FuturesOrderChangeMsg(serde_json::from_str(msg)?))
+    } else if msg.contains("\"subject\":\"stopOrder\"") {
+        Ok(KucoinWebsocketMsg::FuturesStopOrderMsg(serde_json::from_str(msg)?))
+    } else if msg.contains("\"subject\":\"orderMargin.change\"") {
+        Ok(KucoinWebsocketMsg::FuturesOrderMarginMsg(serde_json::from_str(msg)?))
+    } else if msg.contains("\"subject\":\"availableBalance.change\"") {
+        Ok(KucoinWebsocketMsg::FuturesAvailableBalanceMsg(serde_json::from_str(msg)?))
+    } else {
+        Err(anyhow!("No KucoinWebSocketMsg type to parse"))?
+    }
+}
+
 pub async fn close_socket(heartbeat: &mut tokio::task::JoinHandle<()>) -> Result<()> {
     heartbeat.await?;
     Ok(())
@@ -324,8 +376,56 @@ impl Subscribe {
                 private_channel = true;
                 String::from("/spotMarket/tradeOrders")
             },
+            WSTopic::FuturesTicker(ref symbol) => format!("/contractMarket/tickerV2:{}", symbol),
+            WSTopic::FuturesLevel2(ref symbol) => format!("/contractMarket/level2:{}", symbol),
+            WSTopic::FuturesExecution(ref symbol) => format!("/contractMarket/execution:{}", symbol),
+            WSTopic::FuturesInstrument(ref symbol) => format!("/contract/instrument:{}", symbol),
+            WSTopic::FuturesPosition(ref symbol) => {
+                private_channel = true;
+                format!("/contract/position:{}", symbol)
+            },
+            WSTopic::FuturesTradeOrders(ref symbol) => {
+                private_channel = true;
+                match symbol {
+                    Some(s) => format!("/contractMarket/tradeOrders:{}", s),
+                    None => String::from("/contractMarket/tradeOrders"),
+                }
+            },
+            WSTopic::FuturesStopOrders => {
+                private_channel = true;
+                String::from("/contractMarket/advancedOrders")
+            },
+            WSTopic::FuturesWallet => {
+                private_channel = true;
+                String::from("/contractAccount/wallet")
+            },
         };
 
         Subscribe { id, r#type: String::from("subscribe"), topic, private_channel, response: true }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use tokio_tungstenite::tungstenite::Message;
+
+    use crate::model::websocket::KucoinWebsocketMsg;
+    use crate::websocket::parse_message;
+
+    #[test]
+    fn parses_futures_messages_by_topic() {
+        let level2 = r#"{"type":"message","topic":"/contractMarket/level2:XBTUSDTM","subject":"level2","data":{"sequence":18,"change":"5000.0,sell,83","timestamp":1551770400000}}"#;
+        match parse_message(Message::Text(level2.to_string())).unwrap() {
+            KucoinWebsocketMsg::FuturesLevel2Msg(m) => {
+                assert_eq!(m.data.parse_change(), Some(("5000.0", "sell", "83")))
+            },
+            other => panic!("unexpected message {:?}", other),
+        }
+
+        let stop = r#"{"type":"message","topic":"/contractMarket/advancedOrders","subject":"stopOrder","data":{"orderId":"1","symbol":"XBTUSDTM","type":"triggered","orderType":"stop","side":"sell","size":"1","orderPrice":"9000","stop":"down","stopPrice":"9100","stopPriceType":"TP","triggerSuccess":false,"error":"error.createOrder.accountBalanceInsufficient","createdAt":1558074652423,"ts":1558074652423004000}}"#;
+        match parse_message(Message::Text(stop.to_string())).unwrap() {
+            KucoinWebsocketMsg::FuturesStopOrderMsg(m) => assert_eq!(m.data.trigger_success, Some(false)),
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+}
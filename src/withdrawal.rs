@@ -0,0 +1,399 @@
+//! Validated withdrawals
+//!
+//! [`WithdrawalRequest`] builds a withdrawal with an exact decimal amount and checks it against
+//! the currency's chain settings ([`get_currency_detail`](crate::client::Kucoin::get_currency_detail))
+//! and the account's withdrawal quotas
+//! ([`get_withdrawals_quotas`](crate::client::Kucoin::get_withdrawals_quotas)) before anything is
+//! submitted: the chain must exist and allow withdrawals, memos must be given where the chain
+//! needs one, and the amount must respect the chain's precision, its minimum size and the
+//! available balance and remaining daily quota, fee included.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use rust_decimal::Decimal;
+use rust_decimal::RoundingStrategy;
+
+use crate::client::Kucoin;
+use crate::error::Result;
+use crate::model::market::CurrencyDetail;
+use crate::model::user::WithdrawalId;
+use crate::model::user::WithdrawalQuotas;
+use crate::model::APIDatum;
+use crate::model::Method;
+
+/// How the withdrawal fee is paid. When none is given, the exchange charges the main account if
+/// its balance covers the amount and the fee, and deducts the fee from the amount otherwise.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FeeDeductType {
+    /// The fee is taken from the amount, which must therefore exceed it.
+    Internal,
+    /// The fee is charged to the main account balance, the full amount is received.
+    External,
+}
+
+impl FeeDeductType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeDeductType::Internal => "INTERNAL",
+            FeeDeductType::External => "EXTERNAL",
+        }
+    }
+}
+
+/// A submitted withdrawal. `fee` is the fee quoted by the exchange at submission time,
+/// `received` the amount expected to reach `address` and `fee_deduct_type` the requested or
+/// expected way the fee is paid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Withdrawal {
+    pub withdrawal_id: String,
+    pub currency: String,
+    pub chain: String,
+    pub address: String,
+    pub memo: Option<String>,
+    pub amount: Decimal,
+    pub fee: Decimal,
+    pub received: Decimal,
+    pub fee_deduct_type: FeeDeductType,
+    pub is_inner: bool,
+}
+
+/// Amounts a withdrawal was validated with. `fee_deduct_type` is the requested one, or the one
+/// the exchange is expected to apply when none was requested.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WithdrawalCheck {
+    pub fee: Decimal,
+    pub received: Decimal,
+    pub debited: Decimal,
+    pub fee_deduct_type: FeeDeductType,
+}
+
+/// WithdrawalRequest contains a builder pattern for a withdrawal that is only submitted once it
+/// passed every check.
+///
+/// Example:
+/// ``` ignore
+/// use kucoin_api::withdrawal::{FeeDeductType, WithdrawalRequest};
+///
+///     let amount = "12.5".parse()?;
+///     let withdrawal = WithdrawalRequest::new("USDT", "TXYZ...", amount)
+///         .chain("trx")
+///         .fee_deduct_type(FeeDeductType::External)
+///         .build()
+///         .submit(&api)
+///         .await?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithdrawalRequest<'a> {
+    pub currency: &'a str,
+    pub address: &'a str,
+    pub amount: Decimal,
+    pub chain: Option<&'a str>,
+    pub memo: Option<&'a str>,
+    pub is_inner: bool,
+    pub remark: Option<&'a str>,
+    pub fee_deduct_type: Option<FeeDeductType>,
+}
+
+impl<'a> WithdrawalRequest<'a> {
+    pub fn new(currency: &'a str, address: &'a str, amount: Decimal) -> Self {
+        WithdrawalRequest {
+            currency,
+            address,
+            amount,
+            chain: None,
+            memo: None,
+            is_inner: false,
+            remark: None,
+            fee_deduct_type: None,
+        }
+    }
+
+    /// Chain id (e.g. `trx`) or name (e.g. `TRC20`). Required for currencies on several chains.
+    pub fn chain(&mut self, chain: &'a str) -> &mut Self {
+        self.chain = Some(chain);
+        self
+    }
+
+    pub fn memo(&mut self, memo: &'a str) -> &mut Self {
+        self.memo = Some(memo);
+        self
+    }
+
+    /// Withdraws to another KuCoin user, which uses the inner withdrawal fee.
+    pub fn inner(&mut self, is_inner: bool) -> &mut Self {
+        self.is_inner = is_inner;
+        self
+    }
+
+    pub fn remark(&mut self, remark: &'a str) -> &mut Self {
+        self.remark = Some(remark);
+        self
+    }
+
+    pub fn fee_deduct_type(&mut self, fee_deduct_type: FeeDeductType) -> &mut Self {
+        self.fee_deduct_type = Some(fee_deduct_type);
+        self
+    }
+
+    pub fn build(&self) -> Self {
+        self.clone()
+    }
+
+    /// Fetches the currency and quotas and checks the withdrawal against them without submitting.
+    pub async fn validate(&self, api: &Kucoin) -> Result<WithdrawalCheck> {
+        let (currency, quotas) = self.fetch(api).await?;
+        self.check(&currency, &quotas)
+    }
+
+    /// Validates the withdrawal and submits it if every check passed.
+    pub async fn submit(&self, api: &Kucoin) -> Result<Withdrawal> {
+        let (currency, quotas) = self.fetch(api).await?;
+        let check = self.check(&currency, &quotas)?;
+        let chain = self.resolve_chain(&currency)?;
+        let params = self.to_params(&chain);
+        let endpoint = String::from("/api/v1/withdrawals");
+        let url = format!("{}{}", &api.prefix, endpoint);
+        let headers = api.sign_headers(endpoint, Some(&params), None, Method::POST)?;
+        let resp: APIDatum<WithdrawalId> = api.post(url, Some(headers), Some(params)).await?.json().await?;
+        let withdrawal_id = match resp.data {
+            Some(d) => d.withdrawal_id,
+            None => Err(anyhow!("Withdrawal rejected: {}: {}", resp.code, resp.msg.unwrap_or_default()))?,
+        };
+        Ok(Withdrawal {
+            withdrawal_id,
+            currency: self.currency.to_string(),
+            chain,
+            address: self.address.to_string(),
+            memo: self.memo.map(str::to_string),
+            amount: self.amount,
+            fee: check.fee,
+            received: check.received,
+            fee_deduct_type: check.fee_deduct_type,
+            is_inner: self.is_inner,
+        })
+    }
+
+    async fn fetch(&self, api: &Kucoin) -> Result<(CurrencyDetail, WithdrawalQuotas)> {
+        let resp = api.get_currency_detail(self.currency, None).await?;
+        let currency = match resp.data {
+            Some(d) => d,
+            None => Err(anyhow!("Unknown currency {}: {}", self.currency, resp.msg.unwrap_or_default()))?,
+        };
+        let chain = self.resolve_chain(&currency)?;
+        let resp = api.get_withdrawals_quotas(self.currency, Some(&chain)).await?;
+        let quotas = match resp.data {
+            Some(d) => d,
+            None => Err(anyhow!("Failed to get withdrawal quotas: {}", resp.msg.unwrap_or_default()))?,
+        };
+        Ok((currency, quotas))
+    }
+
+    /// Chain id to withdraw on: the requested one, or the only chain of the currency.
+    fn resolve_chain(&self, currency: &CurrencyDetail) -> Result<String> {
+        currency.resolve_chain(self.chain).map(|id| id.to_string())
+    }
+
+    fn check(&self, currency: &CurrencyDetail, quotas: &WithdrawalQuotas) -> Result<WithdrawalCheck> {
+        let chain_id = self.resolve_chain(currency)?;
+        let chain = match currency.chain(&chain_id) {
+            Some(c) => c,
+            None => Err(anyhow!("{} is not available on chain {}", self.currency, chain_id))?,
+        };
+        if !chain.is_withdraw_enabled || !quotas.is_withdraw_enabled {
+            Err(anyhow!("Withdrawals of {} on {} are disabled", self.currency, chain.chain_name))?
+        }
+        if chain.need_tag && self.memo.is_none_or(str::is_empty) {
+            Err(anyhow!("Withdrawals of {} on {} require a memo", self.currency, chain.chain_name))?
+        }
+        if self.amount <= Decimal::ZERO {
+            Err(anyhow!("Withdrawal amount must be positive: {}", self.amount))?
+        }
+        let precision = chain.withdraw_precision.unwrap_or(quotas.precision.max(0) as u32);
+        if self.amount.normalize().scale() > precision {
+            Err(anyhow!("{} exceeds the withdrawal precision of {} decimals", self.amount, precision))?
+        }
+        let min_size = parse_decimal(&chain.withdrawal_min_size)?.max(parse_decimal(&quotas.withdraw_min_size)?);
+        if self.amount < min_size {
+            Err(anyhow!("{} is below the minimum withdrawal of {} {}", self.amount, min_size, self.currency))?
+        }
+        let fee = if self.is_inner {
+            parse_decimal(&quotas.inner_withdraw_min_fee)?
+        } else {
+            let rate = parse_decimal(chain.withdraw_fee_rate.as_deref().unwrap_or_default())?;
+            let proportional = (self.amount * rate).round_dp_with_strategy(precision, RoundingStrategy::AwayFromZero);
+            parse_decimal(&quotas.withdrawal_min_fee)?.max(proportional)
+        };
+        let available = parse_decimal(&quotas.available_amount)?;
+        let fee_deduct_type = match self.fee_deduct_type {
+            Some(t) => t,
+            None if self.amount + fee <= available => FeeDeductType::External,
+            None => FeeDeductType::Internal,
+        };
+        let (received, debited) = match fee_deduct_type {
+            FeeDeductType::Internal => (self.amount - fee, self.amount),
+            FeeDeductType::External => (self.amount, self.amount + fee),
+        };
+        if received <= Decimal::ZERO {
+            Err(anyhow!("{} does not cover the withdrawal fee of {}", self.amount, fee))?
+        }
+        if debited > available {
+            Err(anyhow!("{} {} needed, only {} available", debited, self.currency, available))?
+        }
+        let remaining = parse_decimal(&quotas.remain_amount)?;
+        if self.amount > remaining {
+            Err(anyhow!("{} exceeds the remaining withdrawal quota of {}", self.amount, remaining))?
+        }
+        Ok(WithdrawalCheck { fee, received, debited, fee_deduct_type })
+    }
+
+    fn to_params(&self, chain: &str) -> HashMap<String, String> {
+        let mut params: HashMap<String, String> = HashMap::new();
+        params.insert(String::from("currency"), self.currency.to_string());
+        params.insert(String::from("address"), self.address.to_string());
+        params.insert(String::from("amount"), self.amount.normalize().to_string());
+        params.insert(String::from("chain"), chain.to_string());
+        params.insert(String::from("isInner"), self.is_inner.to_string());
+        if let Some(t) = self.fee_deduct_type {
+            params.insert(String::from("feeDeductType"), t.as_str().to_string());
+        }
+        if let Some(m) = self.memo {
+            params.insert(String::from("memo"), m.to_string());
+        }
+        if let Some(r) = self.remark {
+            params.insert(String::from("remark"), r.to_string());
+        }
+        params
+    }
+}
+
+fn parse_decimal(s: &str) -> Result<Decimal> {
+    if s.is_empty() {
+        return Ok(Decimal::ZERO);
+    }
+    Decimal::from_str(s).map_err(|_| anyhow!("Invalid amount: {}", s).into())
+}
+
+#[cfg(test)]
+mod test {
+    use rust_decimal::Decimal;
+
+    use crate::model::market::CurrencyDetail;
+    use crate::model::user::WithdrawalQuotas;
+    use crate::model::APIDatum;
+    use crate::withdrawal::FeeDeductType;
+    use crate::withdrawal::WithdrawalRequest;
+
+    const USDT_CURRENCY: &str = r#"{"code":"200000","data":{"currency":"USDT","name":"USDT","fullName":"Tether",
+        "precision":8,"confirms":null,"contractAddress":null,"isMarginEnabled":true,"isDebitEnabled":true,"chains":[
+        {"chainName":"TRC20","withdrawalMinSize":"10","depositMinSize":"1","withdrawFeeRate":"0",
+        "withdrawalMinFee":"1","isWithdrawEnabled":true,"isDepositEnabled":true,"confirms":1,"preConfirms":1,
+        "contractAddress":"TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t","withdrawPrecision":4,"maxWithdraw":null,
+        "maxDeposit":null,"needTag":false,"chainId":"trx"},
+        {"chainName":"EOS","withdrawalMinSize":"10","depositMinSize":"1","withdrawFeeRate":"0",
+        "withdrawalMinFee":"1","isWithdrawEnabled":true,"isDepositEnabled":true,"confirms":1,"preConfirms":1,
+        "contractAddress":"tethertether","withdrawPrecision":4,"maxWithdraw":null,"maxDeposit":null,
+        "needTag":true,"chainId":"eos"}]}}"#;
+
+    const USDT_QUOTAS: &str = r#"{"code":"200000","data":{"currency":"USDT","limitBTCAmount":"2.00000000",
+        "usedBTCAmount":"0.00000000","quotaCurrency":"USDT","limitQuotaCurrencyAmount":"100000.00000000",
+        "usedQuotaCurrencyAmount":"0","remainAmount":"500","availableAmount":"100","withdrawMinFee":"1",
+        "innerWithdrawMinFee":"0","withdrawMinSize":"10","isWithdrawEnabled":true,"precision":8,
+        "chain":"TRC20","reason":null,"lockedAmount":"0"}}"#;
+
+    fn fixtures() -> (CurrencyDetail, WithdrawalQuotas) {
+        fixtures_with(USDT_CURRENCY)
+    }
+
+    fn fixtures_with(currency: &str) -> (CurrencyDetail, WithdrawalQuotas) {
+        let currency: APIDatum<CurrencyDetail> = serde_json::from_str(currency).unwrap();
+        let quotas: APIDatum<WithdrawalQuotas> = serde_json::from_str(USDT_QUOTAS).unwrap();
+        (currency.data.unwrap(), quotas.data.unwrap())
+    }
+
+    #[test]
+    fn validates_withdrawals_before_submission() {
+        let (currency, quotas) = fixtures();
+        let amount = |s: &str| s.parse::<Decimal>().unwrap();
+
+        let ok = WithdrawalRequest::new("USDT", "T1", amount("12.5")).chain("TRC20").build();
+        let check = ok.check(&currency, &quotas).unwrap();
+        assert_eq!(check.debited, amount("13.5"));
+        assert_eq!(ok.to_params(&ok.resolve_chain(&currency).unwrap())["chain"], "trx");
+
+        let failing = [
+            WithdrawalRequest::new("USDT", "T1", amount("12.5")).build(),
+            WithdrawalRequest::new("USDT", "T1", amount("12.5")).chain("bsc").build(),
+            WithdrawalRequest::new("USDT", "T1", amount("12.5")).chain("eos").build(),
+            WithdrawalRequest::new("USDT", "T1", amount("12.12345")).chain("trx").build(),
+            WithdrawalRequest::new("USDT", "T1", amount("9.99")).chain("trx").build(),
+            WithdrawalRequest::new("USDT", "T1", amount("99.5"))
+                .chain("trx")
+                .fee_deduct_type(FeeDeductType::External)
+                .build(),
+        ];
+        for request in &failing {
+            assert!(request.check(&currency, &quotas).is_err(), "{:?}", request);
+        }
+        let memo = WithdrawalRequest::new("USDT", "T1", amount("12.5")).chain("eos").memo("123").build();
+        assert!(memo.check(&currency, &quotas).is_ok());
+    }
+
+    #[test]
+    fn applies_fee_deduct_types() {
+        let (currency, quotas) = fixtures();
+        let amount = |s: &str| s.parse::<Decimal>().unwrap();
+        let request = |a: &str, t: Option<FeeDeductType>| {
+            let mut request = WithdrawalRequest::new("USDT", "T1", amount(a));
+            request.chain("trx");
+            if let Some(t) = t {
+                request.fee_deduct_type(t);
+            }
+            request.build()
+        };
+
+        // INTERNAL takes the fee out of the amount.
+        let internal = request("50", Some(FeeDeductType::Internal));
+        assert_eq!(internal.to_params("trx")["feeDeductType"], "INTERNAL");
+        let check = internal.check(&currency, &quotas).unwrap();
+        assert_eq!((check.received, check.debited), (amount("49"), amount("50")));
+
+        // EXTERNAL charges it to the main account on top of the amount.
+        let external = request("50", Some(FeeDeductType::External));
+        assert_eq!(external.to_params("trx")["feeDeductType"], "EXTERNAL");
+        let check = external.check(&currency, &quotas).unwrap();
+        assert_eq!((check.received, check.debited), (amount("50"), amount("51")));
+
+        // Unspecified leaves the choice to the exchange, which charges the main account when it
+        // covers the fee and deducts it from the amount otherwise.
+        let unspecified = request("50", None);
+        assert!(!unspecified.to_params("trx").contains_key("feeDeductType"));
+        assert_eq!(unspecified.check(&currency, &quotas).unwrap().fee_deduct_type, FeeDeductType::External);
+        let check = request("100", None).check(&currency, &quotas).unwrap();
+        assert_eq!(check.fee_deduct_type, FeeDeductType::Internal);
+        assert_eq!((check.received, check.debited), (amount("99"), amount("100")));
+    }
+
+    #[test]
+    fn applies_the_withdrawal_fee_rate() {
+        let trc20 = USDT_CURRENCY.replacen(r#""withdrawFeeRate":"0""#, r#""withdrawFeeRate":"0.05""#, 1);
+        let (currency, quotas) = fixtures_with(&trc20);
+        let amount = |s: &str| s.parse::<Decimal>().unwrap();
+        let request = |a: &str, t: FeeDeductType| {
+            WithdrawalRequest::new("USDT", "T1", amount(a)).chain("trx").fee_deduct_type(t).build()
+        };
+
+        // 5% of 12.5 is below the minimum fee of 1.
+        let check = request("12.5", FeeDeductType::External).check(&currency, &quotas).unwrap();
+        assert_eq!((check.fee, check.debited), (amount("1"), amount("13.5")));
+
+        let check = request("50", FeeDeductType::Internal).check(&currency, &quotas).unwrap();
+        assert_eq!((check.fee, check.received, check.debited), (amount("2.5"), amount("47.5"), amount("50")));
+
+        // 98 + 4.9 exceeds the available 100, which the minimum fee alone would not.
+        assert!(request("98", FeeDeductType::External).check(&currency, &quotas).is_err());
+        let implicit = WithdrawalRequest::new("USDT", "T1", amount("98")).chain("trx").build();
+        assert_eq!(implicit.check(&currency, &quotas).unwrap().fee_deduct_type, FeeDeductType::Internal);
+    }
+}
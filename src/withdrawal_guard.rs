@@ -0,0 +1,366 @@
+//! Withdrawal allow-list and two-step approval
+//!
+//! [`WithdrawalGuard`] only lets withdrawals through to allow-listed (currency, chain, address,
+//! memo) destinations, chains being resolved to their id through a [`CurrencyRegistry`], and never
+//! submits one directly: a withdrawal is first staged, which returns
+//! a one-time token, and only submitted when a second call approves that token before it expires.
+//! Approved withdrawals are submitted through [`WithdrawalRequest::submit`], so the usual chain,
+//! precision and quota checks still apply. Every stage is appended to an [`AuditEntry`] log, kept
+//! in memory and optionally written to a JSON lines file.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::anyhow;
+use rust_decimal::Decimal;
+
+use crate::client::Kucoin;
+use crate::currency_registry::CurrencyRegistry;
+use crate::error::Result;
+use crate::utils::get_time;
+use crate::withdrawal::FeeDeductType;
+use crate::withdrawal::Withdrawal;
+use crate::withdrawal::WithdrawalRequest;
+
+/// An allowed withdrawal destination. Currency and chain are compared ignoring case, address and
+/// memo exactly. The chain may be given by id (e.g. `trx`) or name (e.g. `TRC20`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowedAddress {
+    pub currency: String,
+    pub chain: String,
+    pub address: String,
+    pub memo: Option<String>,
+}
+
+impl AllowedAddress {
+    pub fn new(currency: &str, chain: &str, address: &str, memo: Option<&str>) -> Self {
+        AllowedAddress {
+            currency: currency.to_uppercase(),
+            chain: chain.to_lowercase(),
+            address: address.to_string(),
+            memo: memo.filter(|m| !m.is_empty()).map(str::to_string),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditStage {
+    Staged,
+    Rejected,
+    Approved,
+    Submitted,
+    Failed,
+    Expired,
+    Cancelled,
+}
+
+/// One audit log line. `ts` is the local time in milliseconds.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub ts: u64,
+    pub stage: AuditStage,
+    pub token: Option<String>,
+    pub currency: String,
+    pub chain: Option<String>,
+    pub address: String,
+    pub memo: Option<String>,
+    pub amount: Decimal,
+    pub detail: Option<String>,
+}
+
+/// A withdrawal waiting for approval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedWithdrawal {
+    pub token: String,
+    pub currency: String,
+    pub chain: String,
+    pub address: String,
+    pub memo: Option<String>,
+    pub amount: Decimal,
+    pub is_inner: bool,
+    pub remark: Option<String>,
+    pub fee_deduct_type: Option<FeeDeductType>,
+    pub staged_at: Instant,
+}
+
+impl StagedWithdrawal {
+    pub fn request(&self) -> WithdrawalRequest<'_> {
+        let mut request = WithdrawalRequest::new(&self.currency, &self.address, self.amount);
+        request.chain(&self.chain).inner(self.is_inner);
+        if let Some(t) = self.fee_deduct_type {
+            request.fee_deduct_type(t);
+        }
+        if let Some(m) = &self.memo {
+            request.memo(m);
+        }
+        if let Some(r) = &self.remark {
+            request.remark(r);
+        }
+        request
+    }
+
+    fn destination(&self) -> AllowedAddress {
+        AllowedAddress::new(&self.currency, &self.chain, &self.address, self.memo.as_deref())
+    }
+}
+
+struct GuardState {
+    allowed: HashSet<AllowedAddress>,
+    pending: HashMap<String, StagedWithdrawal>,
+    log: Vec<AuditEntry>,
+    audit_file: Option<File>,
+}
+
+/// Allow-list and approval guard around withdrawals. Cloning yields another handle to the same
+/// guard.
+///
+/// Example:
+/// ``` ignore
+/// use kucoin_api::withdrawal_guard::{AllowedAddress, WithdrawalGuard};
+///
+///     let guard = WithdrawalGuard::new(Duration::from_secs(300), registry).audit_file("withdrawals.jsonl")?;
+///     guard.allow(AllowedAddress::new("USDT", "TRC20", "TXYZ...", None));
+///
+///     let token = guard.stage(WithdrawalRequest::new("USDT", "TXYZ...", amount).chain("trx"))?;
+///     // ... second operator reviews guard.pending() ...
+///     let withdrawal = guard.approve(&api, &token).await?;
+/// ```
+#[derive(Clone)]
+pub struct WithdrawalGuard {
+    approval_ttl: Duration,
+    registry: CurrencyRegistry,
+    state: Arc<Mutex<GuardState>>,
+}
+
+impl WithdrawalGuard {
+    /// Staged withdrawals must be approved within `approval_ttl`. Chain names are resolved to
+    /// their id with `registry`.
+    pub fn new(approval_ttl: Duration, registry: CurrencyRegistry) -> Self {
+        let state = GuardState { allowed: HashSet::new(), pending: HashMap::new(), log: Vec::new(), audit_file: None };
+        WithdrawalGuard { approval_ttl, registry, state: Arc::new(Mutex::new(state)) }
+    }
+
+    /// Also appends audit entries to the JSON lines file at `path`, creating it if needed.
+    pub fn audit_file(self, path: impl AsRef<Path>) -> Result<Self> {
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        self.lock().audit_file = Some(file);
+        Ok(self)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GuardState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Allow-lists an address. Its chain is resolved to the chain id when the registry knows the
+    /// currency, a chain it cannot resolve never matches a staged withdrawal.
+    pub fn allow(&self, address: AllowedAddress) {
+        let address = self.resolve(address);
+        self.lock().allowed.insert(address);
+    }
+
+    /// Removes an address from the allow-list. Staged withdrawals to it can no longer be approved.
+    pub fn revoke(&self, address: &AllowedAddress) -> bool {
+        let address = self.resolve(address.clone());
+        self.lock().allowed.remove(&address)
+    }
+
+    fn resolve(&self, mut address: AllowedAddress) -> AllowedAddress {
+        if let Ok(id) = self.registry.resolve_chain(&address.currency, Some(&address.chain)) {
+            address.chain = id.to_string();
+        }
+        address
+    }
+
+    pub fn allowed(&self) -> Vec<AllowedAddress> {
+        self.lock().allowed.iter().cloned().collect()
+    }
+
+    pub fn pending(&self) -> Vec<StagedWithdrawal> {
+        self.lock().pending.values().cloned().collect()
+    }
+
+    pub fn audit_log(&self) -> Vec<AuditEntry> {
+        self.lock().log.clone()
+    }
+
+    /// Stages a withdrawal to an allow-listed destination and returns the token approving it.
+    /// The request must name its chain, by id or name.
+    pub fn stage(&self, request: &WithdrawalRequest<'_>) -> Result<String> {
+        let mut staged = StagedWithdrawal {
+            token: token()?,
+            currency: request.currency.to_string(),
+            chain: request.chain.unwrap_or_default().to_string(),
+            address: request.address.to_string(),
+            memo: request.memo.map(str::to_string),
+            amount: request.amount,
+            is_inner: request.is_inner,
+            remark: request.remark.map(str::to_string),
+            fee_deduct_type: request.fee_deduct_type,
+            staged_at: Instant::now(),
+        };
+        let mut state = self.lock();
+        if staged.chain.is_empty() {
+            Self::audit(&mut state, AuditStage::Rejected, &staged, Some("no chain given"));
+            Err(anyhow!("Guarded withdrawals must name their chain"))?
+        }
+        match self.registry.resolve_chain(&staged.currency, Some(&staged.chain)) {
+            Ok(id) => staged.chain = id.to_string(),
+            Err(e) => {
+                Self::audit(&mut state, AuditStage::Rejected, &staged, Some("unknown chain"));
+                return Err(e);
+            },
+        }
+        if !state.allowed.contains(&staged.destination()) {
+            Self::audit(&mut state, AuditStage::Rejected, &staged, Some("destination not allow-listed"));
+            Err(anyhow!("{} {} on {} is not allow-listed", staged.currency, staged.address, staged.chain))?
+        }
+        Self::audit(&mut state, AuditStage::Staged, &staged, None);
+        let token = staged.token.clone();
+        state.pending.insert(token.clone(), staged);
+        Ok(token)
+    }
+
+    /// Approves a staged withdrawal and submits it. The token is consumed whatever the outcome.
+    pub async fn approve(&self, api: &Kucoin, token: &str) -> Result<Withdrawal> {
+        let staged = {
+            let mut state = self.lock();
+            let staged = match state.pending.remove(token) {
+                Some(s) => s,
+                None => Err(anyhow!("Unknown or already used withdrawal token"))?,
+            };
+            if staged.staged_at.elapsed() > self.approval_ttl {
+                Self::audit(&mut state, AuditStage::Expired, &staged, None);
+                Err(anyhow!("Withdrawal approval expired"))?
+            }
+            if !state.allowed.contains(&staged.destination()) {
+                Self::audit(&mut state, AuditStage::Rejected, &staged, Some("destination revoked"));
+                Err(anyhow!("{} is no longer allow-listed", staged.address))?
+            }
+            Self::audit(&mut state, AuditStage::Approved, &staged, None);
+            staged
+        };
+        let result = staged.request().submit(api).await;
+        let mut state = self.lock();
+        match &result {
+            Ok(w) => Self::audit(&mut state, AuditStage::Submitted, &staged, Some(&w.withdrawal_id)),
+            Err(e) => Self::audit(&mut state, AuditStage::Failed, &staged, Some(&format!("{:?}", e))),
+        }
+        result
+    }
+
+    /// Drops a staged withdrawal without submitting it.
+    pub fn cancel(&self, token: &str) -> bool {
+        let mut state = self.lock();
+        match state.pending.remove(token) {
+            Some(staged) => {
+                Self::audit(&mut state, AuditStage::Cancelled, &staged, None);
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn audit(state: &mut GuardState, stage: AuditStage, staged: &StagedWithdrawal, detail: Option<&str>) {
+        let entry = AuditEntry {
+            ts: get_time() as u64,
+            stage,
+            token: Some(staged.token.clone()),
+            currency: staged.currency.clone(),
+            chain: Some(staged.chain.clone()).filter(|c| !c.is_empty()),
+            address: staged.address.clone(),
+            memo: staged.memo.clone(),
+            amount: staged.amount,
+            detail: detail.map(str::to_string),
+        };
+        tracing::info!(?entry, "Withdrawal audit");
+        if let Some(file) = state.audit_file.as_mut() {
+            let line = serde_json::to_string(&entry).map(|l| l + "\n");
+            if let Err(e) = line.map_err(std::io::Error::from).and_then(|l| file.write_all(l.as_bytes())) {
+                tracing::error!(%e, "Failed to write withdrawal audit log");
+            }
+        }
+        state.log.push(entry);
+    }
+}
+
+/// 128 bit token from the OS random number generator.
+fn token() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    if let Err(e) = getrandom::getrandom(&mut bytes) {
+        Err(anyhow!("Failed to generate a withdrawal token: {}", e))?
+    }
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::currency_registry::CurrencyRegistry;
+    use crate::model::market::CurrencyDetail;
+    use crate::model::APIData;
+    use crate::withdrawal::WithdrawalRequest;
+    use crate::withdrawal_guard::AllowedAddress;
+    use crate::withdrawal_guard::AuditStage;
+    use crate::withdrawal_guard::WithdrawalGuard;
+
+    #[test]
+    fn stages_only_allow_listed_withdrawals() {
+        let resp: APIData<CurrencyDetail> = serde_json::from_str(
+            r#"{"code":"200000","data":[{"currency":"USDT","name":"USDT","fullName":"Tether","precision":8,
+            "confirms":null,"contractAddress":null,"isMarginEnabled":true,"isDebitEnabled":true,"chains":[
+            {"chainName":"TRC20","withdrawalMinSize":"10","depositMinSize":"1","withdrawFeeRate":"0",
+            "withdrawalMinFee":"1","isWithdrawEnabled":true,"isDepositEnabled":true,"confirms":1,"preConfirms":1,
+            "contractAddress":"TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t","withdrawPrecision":6,
+            "maxWithdraw":null,"maxDeposit":null,"needTag":false,"chainId":"trx"}]}]}"#,
+        )
+        .unwrap();
+        let registry = CurrencyRegistry::new();
+        registry.load(resp.data.unwrap());
+        let guard = WithdrawalGuard::new(Duration::from_secs(60), registry);
+        guard.allow(AllowedAddress::new("usdt", "TRC20", "T1", None));
+        assert_eq!(guard.allowed()[0].chain, "trx");
+        let amount = "12.5".parse().unwrap();
+
+        let token = guard.stage(WithdrawalRequest::new("USDT", "T1", amount).chain("trx")).unwrap();
+        assert_eq!(token.len(), 32);
+        assert_eq!(guard.pending().len(), 1);
+        let by_name = guard.stage(WithdrawalRequest::new("USDT", "T1", amount).chain("TRC20")).unwrap();
+        assert_eq!(guard.pending().iter().find(|s| s.token == by_name).unwrap().chain, "trx");
+        assert!(guard.cancel(&by_name));
+        assert!(guard.stage(WithdrawalRequest::new("USDT", "T1", amount).chain("sol")).is_err());
+        assert!(guard.stage(WithdrawalRequest::new("USDT", "T2", amount).chain("trx")).is_err());
+        assert!(guard.stage(&WithdrawalRequest::new("USDT", "T1", amount)).is_err());
+        assert!(guard.stage(WithdrawalRequest::new("USDT", "T1", amount).chain("trx").memo("1")).is_err());
+
+        let second = guard.stage(WithdrawalRequest::new("USDT", "T1", amount).chain("trx")).unwrap();
+        assert_ne!(token, second);
+        assert!(guard.cancel(&token));
+        assert!(!guard.cancel(&token));
+
+        let stages: Vec<AuditStage> = guard.audit_log().iter().map(|e| e.stage).collect();
+        assert_eq!(stages, vec![
+            AuditStage::Staged,
+            AuditStage::Staged,
+            AuditStage::Cancelled,
+            AuditStage::Rejected,
+            AuditStage::Rejected,
+            AuditStage::Rejected,
+            AuditStage::Rejected,
+            AuditStage::Staged,
+            AuditStage::Cancelled,
+        ]);
+    }
+}